@@ -267,7 +267,9 @@ config["custom/hyprwhspr"] = {
     "format": "{text}",
     "interval": 1,
     "tooltip": True,
-    "on-click": "walker --provider menus:hyprwhspr"
+    "on-click": "walker --provider menus:hyprwhspr",
+    "on-scroll-up": "hyprwhspr-rs ctl profile next",
+    "on-scroll-down": "hyprwhspr-rs ctl profile previous"
 }
 
 # Add to modules-right (first position) if not already there
@@ -1,21 +1,36 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use std::collections::VecDeque;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc,
 };
 use std::thread::{self, JoinHandle};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle as TaskHandle;
+use tokio::time::MissedTickBehavior;
 use tracing::{debug, error, info, warn};
 
 use crate::audio::{
-    capture::RecordingSession, AudioCapture, AudioFeedback, CapturedAudio, FastVad, FastVadOutcome,
+    capture::RecordingSession, AudioCapture, AudioFeedback, CapturedAudio, EndOfSpeechDetector,
+    FastVad, FastVadOutcome,
 };
 use crate::benchmark::BenchmarkRecorder;
-use crate::config::{Config, ConfigManager, ShortcutsConfig, TranscriptionProvider};
-use crate::input::{GlobalShortcuts, ShortcutEvent, ShortcutKind, ShortcutPhase, TextInjector};
+use crate::config::{
+    Config, ConfigManager, ShortcutAction, ShortcutBinding, ShortcutsConfig, TranscriptionProvider,
+};
+use crate::control::{
+    ControlCommand, ControlMessage, ControlResponse, ControlServer, DaemonStatus,
+};
+use crate::events::{DaemonEvent, DaemonState, EventBus, StopReason};
+use crate::input::replacements::SpeechReplacements;
+use crate::input::{
+    focused_window, ActiveWindow, GlobalShortcuts, ShortcutEvent, ShortcutKind, ShortcutPhase,
+    TextInjector, UndoState,
+};
+use crate::llm::LlmPostProcessor;
 use crate::status::{StatusWriter, WaybarState};
-use crate::transcription::{TranscriptionBackend, TranscriptionResult};
+use crate::transcription::{TranscriptionChain, TranscriptionResult, TranscriptionTask};
 use crate::whisper::WhisperVadOptions;
 
 struct ShortcutListener {
@@ -64,6 +79,7 @@ impl ShortcutListener {
     fn spawn(
         shortcut: String,
         kind: ShortcutKind,
+        binding: usize,
         tx: mpsc::Sender<ShortcutEvent>,
     ) -> Result<Self> {
         let stop_flag = Arc::new(AtomicBool::new(false));
@@ -71,16 +87,18 @@ impl ShortcutListener {
         let runner_tx = tx.clone();
         let shortcut_name = shortcut.clone();
 
-        let handle = thread::spawn(move || match GlobalShortcuts::new(&shortcut, kind) {
-            Ok(shortcuts) => {
-                if let Err(e) = shortcuts.run(runner_tx, runner_flag) {
-                    error!("Global shortcuts error: {}", e);
+        let handle = thread::spawn(
+            move || match GlobalShortcuts::new(&shortcut, kind, binding) {
+                Ok(shortcuts) => {
+                    if let Err(e) = shortcuts.run(runner_tx, runner_flag) {
+                        error!("Global shortcuts error: {}", e);
+                    }
                 }
-            }
-            Err(e) => {
-                error!("Failed to initialize global shortcuts: {}", e);
-            }
-        });
+                Err(e) => {
+                    error!("Failed to initialize global shortcuts: {}", e);
+                }
+            },
+        );
 
         Ok(Self {
             stop_flag,
@@ -94,10 +112,11 @@ impl ShortcutListener {
         &mut self,
         shortcut: String,
         kind: ShortcutKind,
+        binding: usize,
         tx: mpsc::Sender<ShortcutEvent>,
     ) -> Result<()> {
         self.stop();
-        *self = Self::spawn(shortcut, kind, tx)?;
+        *self = Self::spawn(shortcut, kind, binding, tx)?;
         Ok(())
     }
 
@@ -121,10 +140,14 @@ impl Drop for ShortcutListener {
     }
 }
 
+/// How often an active recording is inspected while it is still running
+const LIVE_MONITOR_INTERVAL: Duration = Duration::from_millis(100);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum RecordingTrigger {
-    HoldShortcut,
-    PressShortcut,
+    /// Index into `shortcut_bindings`
+    Shortcut(usize),
+    Control,
 }
 
 #[derive(Debug, Clone)]
@@ -139,6 +162,159 @@ struct PreprocessedAudio {
     report: Option<FastVadSummary>,
 }
 
+/// Watches the capture buffer of a recording while it is still running.
+struct LiveMonitor {
+    /// Samples already handed to the detectors
+    offset: usize,
+    end_of_speech: Option<EndOfSpeechDetector>,
+    /// Forced stop at `max_recording_secs`
+    deadline: Option<Instant>,
+    /// Advance warning before the deadline; cleared once it fired
+    warn_at: Option<Instant>,
+    stream: Option<ChunkStream>,
+}
+
+/// Cuts a running recording into chunks at pauses in speech (`streaming.enabled`).
+struct ChunkStream {
+    pauses: EndOfSpeechDetector,
+    /// Capture offset where the next chunk begins
+    chunk_start: usize,
+    min_chunk_samples: usize,
+    /// Chunks handed to the transcription queue so far
+    chunks_sent: usize,
+}
+
+/// Fans daemon events out to subscribers, with the Waybar status file as one consumer.
+#[derive(Clone)]
+struct EventEmitter {
+    status_writer: StatusWriter,
+    events: EventBus,
+}
+
+impl EventEmitter {
+    fn emit(&self, event: DaemonEvent) {
+        if let Err(err) = self.status_writer.apply(&event) {
+            warn!("Failed to update status for {:?}: {}", event, err);
+        }
+        self.events.publish(event);
+    }
+}
+
+/// Transcription and injection of one recording, detached from the app loop.
+struct TranscriptionJob {
+    /// Recording the audio came from
+    recording: u64,
+    audio: Vec<f32>,
+    transcriber: Arc<TranscriptionChain>,
+    postprocessor: Option<Arc<LlmPostProcessor>>,
+    text_injector: Arc<Mutex<TextInjector>>,
+    emitter: EventEmitter,
+    benchmark: Option<BenchmarkRecorder>,
+    /// Streamed chunk following earlier text from the same recording
+    continues_text: bool,
+    task: TranscriptionTask,
+    /// Run the voice commands in the text instead of pasting it
+    commands_only: bool,
+}
+
+struct ActiveJob {
+    id: u64,
+    recording: u64,
+    handle: TaskHandle<()>,
+}
+
+struct JobOutcome {
+    id: u64,
+    result: Result<()>,
+}
+
+impl TranscriptionJob {
+    async fn run(mut self) -> Result<()> {
+        let (
+            provider,
+            TranscriptionResult {
+                text,
+                metrics,
+                language,
+                ..
+            },
+        ) = self.transcriber.transcribe(self.audio, self.task).await?;
+
+        if let Some(benchmark) = self.benchmark.as_mut() {
+            benchmark.record_backend_metrics(metrics);
+            benchmark.record_provider(provider.label());
+        }
+
+        if text.trim().is_empty() {
+            warn!("Empty transcription, nothing to inject");
+            finish_skipped(self.benchmark.take());
+            return Ok(());
+        }
+
+        match language {
+            Some(language) => info!("📝 Transcription ({}): \"{}\"", language, text),
+            None => info!("📝 Transcription: \"{}\"", text),
+        }
+
+        let text = match &self.postprocessor {
+            Some(postprocessor) => postprocessor.process(&text).await,
+            None => text,
+        };
+
+        // The status writer saves this to history for Walker/Elephant integration
+        self.emitter.emit(DaemonEvent::Transcription {
+            text: text.clone(),
+            provider: provider.label().to_string(),
+        });
+
+        let mut injector = self.text_injector.lock().await;
+
+        let injection_start = Instant::now();
+        if let Some(benchmark) = self.benchmark.as_mut() {
+            benchmark.mark_injection_start(injection_start);
+        }
+
+        debug!("⌨️  Injecting text into active application...");
+        let injected = if self.commands_only {
+            injector.run_commands(&text).await
+        } else if self.continues_text {
+            injector.inject_continuation(&text).await
+        } else {
+            injector.inject_text(&text).await
+        };
+        if let Err(err) = injected {
+            self.emitter.emit(DaemonEvent::InjectionFailed {
+                message: format!("{:#}", err),
+            });
+            return Err(err);
+        }
+        self.emitter.emit(DaemonEvent::Injected);
+
+        let injection_end = Instant::now();
+        if let Some(benchmark) = self.benchmark.as_mut() {
+            benchmark.mark_injection_end(injection_end);
+        }
+
+        if let Some(benchmark) = self.benchmark.take() {
+            if let Some(summary) = benchmark.finalize() {
+                info!(message = %format_args!("\n{}", summary));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Log the benchmark for a recording that never reached injection.
+fn finish_skipped(benchmark: Option<BenchmarkRecorder>) {
+    if let Some(mut benchmark) = benchmark {
+        benchmark.mark_injection_skipped(Instant::now());
+        if let Some(summary) = benchmark.finalize() {
+            info!(message = %format_args!("\n{}", summary));
+        }
+    }
+}
+
 fn build_vad_options(config_manager: &ConfigManager, config: &Config) -> WhisperVadOptions {
     let whisper_vad = &config.transcription.whisper_cpp.vad;
     WhisperVadOptions {
@@ -153,6 +329,78 @@ fn build_vad_options(config_manager: &ConfigManager, config: &Config) -> Whisper
     }
 }
 
+/// The state the daemon is left in; a live recording reports its own state
+fn settled_event(recording: bool, processing: bool) -> Option<DaemonEvent> {
+    if recording {
+        None
+    } else if processing {
+        Some(DaemonEvent::Processing)
+    } else {
+        Some(DaemonEvent::Ready)
+    }
+}
+
+/// `Cancelled` for the stage that was cancelled, then the state the daemon settles in
+fn cancel_events(stage: DaemonState, recording: bool, processing: bool) -> Vec<DaemonEvent> {
+    let mut events = vec![DaemonEvent::Cancelled { stage }];
+    events.extend(settled_event(recording, processing));
+    events
+}
+
+/// Apply `profiles` to `base` in order, so later ones refine earlier ones
+fn layered_config(base: &Config, profiles: [Option<&str>; 3]) -> Result<Config> {
+    let mut config = base.clone();
+    for name in profiles.into_iter().flatten() {
+        config = config.with_profile(name)?;
+    }
+    Ok(config)
+}
+
+/// Record-style bindings held down report their release; everything else fires on press
+fn listener_kind(binding: &ShortcutBinding) -> ShortcutKind {
+    if binding.hold && binding.action.records() {
+        ShortcutKind::Hold
+    } else {
+        ShortcutKind::Press
+    }
+}
+
+fn binding_label(binding: &ShortcutBinding) -> &'static str {
+    match binding.action {
+        ShortcutAction::Dictate if listener_kind(binding) == ShortcutKind::Hold => "Hold",
+        ShortcutAction::Dictate => "Press",
+        ShortcutAction::Translate => "Translate",
+        ShortcutAction::Command => "Command",
+        ShortcutAction::Cancel => "Cancel",
+        ShortcutAction::Reinject => "Re-inject",
+    }
+}
+
+fn audio_feedback_needs_refresh(current: &Config, new: &Config) -> bool {
+    current.audio_feedback != new.audio_feedback
+        || current.start_sound_path != new.start_sound_path
+        || current.stop_sound_path != new.stop_sound_path
+        || current.start_sound_volume != new.start_sound_volume
+        || current.stop_sound_volume != new.stop_sound_volume
+}
+
+/// Whether the settings the text injector and its speech replacements are built from differ
+fn injector_needs_refresh(current: &Config, new: &Config) -> bool {
+    current.word_overrides != new.word_overrides
+        || current.word_override_rules != new.word_override_rules
+        || current.voice_commands != new.voice_commands
+        || current.speech_replacements != new.speech_replacements
+        || current.paste_hints != new.paste_hints
+        || current.shift_paste != new.shift_paste
+        || current.global_paste_shortcut != new.global_paste_shortcut
+        || current.paste_method != new.paste_method
+        || current.type_delay_ms != new.type_delay_ms
+        || current.trailing_whitespace != new.trailing_whitespace
+        // Pick the speech replacement pack when `packs` is unset
+        || current.transcription.language != new.transcription.language
+        || current.transcription.translate != new.transcription.translate
+}
+
 fn fast_vad_allowed(config: &Config) -> bool {
     if !config.fast_vad.enabled {
         return false;
@@ -171,24 +419,52 @@ pub struct HyprwhsprApp {
     config_manager: ConfigManager,
     audio_capture: AudioCapture,
     audio_feedback: AudioFeedback,
-    transcriber: TranscriptionBackend,
+    transcriber: Arc<TranscriptionChain>,
+    /// `llm_postprocess`, applied between transcription and injection
+    postprocessor: Option<Arc<LlmPostProcessor>>,
     fast_vad: Option<FastVad>,
     text_injector: Arc<Mutex<TextInjector>>,
-    status_writer: StatusWriter,
+    /// Shared by every injector `text_injector` held, so undo survives a rebuild
+    undo_state: UndoState,
+    emitter: EventEmitter,
     shortcut_tx: mpsc::Sender<ShortcutEvent>,
     shortcut_rx: Option<mpsc::Receiver<ShortcutEvent>>,
-    press_listener: Option<ShortcutListener>,
-    hold_listener: Option<ShortcutListener>,
+    /// One listener per entry of `shortcut_bindings`, in the same order
+    shortcut_listeners: Vec<ShortcutListener>,
+    shortcut_bindings: Vec<ShortcutBinding>,
+    /// Config as loaded from disk
+    base_config: Config,
+    /// Profile selected from the config, the CLI or Waybar
+    active_profile: Option<String>,
+    /// Profile picked for the focused window at the start of the last recording
+    window_profile: Option<String>,
+    /// Profile of the shortcut that started the last recording
+    shortcut_profile: Option<String>,
+    /// `base_config` with `active_profile`, `window_profile` and `shortcut_profile` applied
+    /// in that order; what the components were built from
     current_config: Config,
     recording_session: Option<RecordingSession>,
     recording_trigger: Option<RecordingTrigger>,
+    /// Task for the jobs of the current (or last) recording
+    recording_task: TranscriptionTask,
+    /// The current (or last) recording only runs voice commands
+    recording_commands_only: bool,
+    /// Id of the current (or last) recording, shared by the jobs it queues
+    recording_id: u64,
+    live_monitor: Option<LiveMonitor>,
     benchmark: Option<BenchmarkRecorder>,
-    is_processing: bool,
+    job_tx: mpsc::Sender<JobOutcome>,
+    job_rx: Option<mpsc::Receiver<JobOutcome>>,
+    active_job: Option<ActiveJob>,
+    pending_jobs: VecDeque<TranscriptionJob>,
+    next_job_id: u64,
 }
 
 impl HyprwhsprApp {
     pub fn new(config_manager: ConfigManager) -> Result<Self> {
-        let config = config_manager.get();
+        let base_config = config_manager.get();
+        let active_profile = base_config.active_profile.clone();
+        let config = layered_config(&base_config, [active_profile.as_deref(), None, None])?;
 
         let audio_capture =
             AudioCapture::new(config.audio_device).context("Failed to initialize audio capture")?;
@@ -205,7 +481,7 @@ impl HyprwhsprApp {
 
         let vad_options = build_vad_options(&config_manager, &config);
 
-        let transcriber = TranscriptionBackend::build(&config_manager, &config, vad_options)
+        let mut transcriber = TranscriptionChain::build(&config_manager, &config, vad_options)
             .context("Failed to configure transcription backend")?;
 
         transcriber
@@ -217,18 +493,24 @@ impl HyprwhsprApp {
             transcriber.provider().label()
         );
 
-        let text_injector = TextInjector::new(
-            config.shift_paste,
-            config.global_paste_shortcut,
-            config.paste_hints.shift.clone(),
-            config.word_overrides.clone(),
-            config.auto_copy_clipboard,
-        )?;
+        let postprocessor = LlmPostProcessor::new(&config.llm_postprocess)
+            .context("Failed to configure LLM post-processing")?
+            .map(Arc::new);
+
+        let speech_replacements =
+            SpeechReplacements::load(&config, &config_manager.get_speech_packs_dir())
+                .context("Failed to load speech replacements")?;
+
+        let undo_state = UndoState::default();
+        let text_injector =
+            TextInjector::new(&config, speech_replacements)?.with_undo_state(undo_state.clone());
 
         let status_writer = StatusWriter::new()?;
+        status_writer.set_profile(active_profile.as_deref());
         status_writer.set_state(WaybarState::Inactive, "Ready")?;
 
         let (shortcut_tx, shortcut_rx) = mpsc::channel(10);
+        let (job_tx, job_rx) = mpsc::channel(4);
 
         let fast_vad = if fast_vad_allowed(&config) {
             FastVad::maybe_new(&config.fast_vad, audio_capture.sample_rate_hint())
@@ -255,19 +537,36 @@ impl HyprwhsprApp {
             config_manager,
             audio_capture,
             audio_feedback,
-            transcriber,
+            transcriber: Arc::new(transcriber),
+            postprocessor,
             fast_vad,
             text_injector: Arc::new(Mutex::new(text_injector)),
-            status_writer,
+            undo_state,
+            emitter: EventEmitter {
+                status_writer,
+                events: EventBus::new(),
+            },
             shortcut_tx,
             shortcut_rx: Some(shortcut_rx),
-            press_listener: None,
-            hold_listener: None,
+            shortcut_listeners: Vec::new(),
+            shortcut_bindings: Vec::new(),
+            base_config,
+            active_profile,
+            window_profile: None,
+            shortcut_profile: None,
             current_config: config,
             recording_session: None,
             recording_trigger: None,
+            recording_task: TranscriptionTask::default(),
+            recording_commands_only: false,
+            recording_id: 0,
+            live_monitor: None,
             benchmark: None,
-            is_processing: false,
+            job_tx,
+            job_rx: Some(job_rx),
+            active_job: None,
+            pending_jobs: VecDeque::new(),
+            next_job_id: 0,
         })
     }
 
@@ -278,11 +577,24 @@ impl HyprwhsprApp {
             .shortcut_rx
             .take()
             .expect("shortcut receiver already consumed");
+        let mut job_rx = self.job_rx.take().expect("job receiver already consumed");
         self.ensure_shortcut_listeners(self.current_config.shortcuts.clone())?;
         self.log_shortcut_configuration(&self.current_config.shortcuts);
 
+        let (control_tx, mut control_rx) = mpsc::channel::<ControlMessage>(8);
+        let _control_server = match ControlServer::bind(control_tx, self.emitter.events.clone()) {
+            Ok(server) => Some(server),
+            Err(err) => {
+                warn!("Control socket unavailable: {:#}", err);
+                None
+            }
+        };
+
         let mut config_rx = self.config_manager.subscribe();
 
+        let mut monitor = tokio::time::interval(LIVE_MONITOR_INTERVAL);
+        monitor.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
         loop {
             tokio::select! {
                 event = shortcut_rx.recv() => {
@@ -298,6 +610,18 @@ impl HyprwhsprApp {
                         }
                     }
                 }
+                _ = monitor.tick(), if self.live_monitor.is_some() => {
+                    if let Err(e) = self.monitor_recording().await {
+                        error!("Error monitoring recording: {:#}", e);
+                    }
+                }
+                Some(outcome) = job_rx.recv() => {
+                    self.finish_transcription(outcome);
+                }
+                Some(message) = control_rx.recv() => {
+                    let response = self.handle_control(message.command).await;
+                    let _ = message.reply.send(response);
+                }
                 result = config_rx.changed() => {
                     match result {
                         Ok(()) => {
@@ -319,72 +643,130 @@ impl HyprwhsprApp {
     }
 
     fn ensure_shortcut_listeners(&mut self, shortcuts: ShortcutsConfig) -> Result<()> {
-        self.ensure_listener(ShortcutKind::Press, shortcuts.press.clone())?;
-        self.ensure_listener(ShortcutKind::Hold, shortcuts.hold.clone())
-    }
-
-    fn ensure_listener(&mut self, kind: ShortcutKind, shortcut: Option<String>) -> Result<()> {
-        let slot = match kind {
-            ShortcutKind::Press => &mut self.press_listener,
-            ShortcutKind::Hold => &mut self.hold_listener,
-        };
-
-        match shortcut {
-            Some(ref target) => {
-                if let Some(listener) = slot {
-                    if listener.matches(target, kind) {
-                        return Ok(());
-                    }
-                    listener.restart(target.clone(), kind, self.shortcut_tx.clone())?;
-                } else {
-                    let listener =
-                        ShortcutListener::spawn(target.clone(), kind, self.shortcut_tx.clone())?;
-                    *slot = Some(listener);
-                }
-            }
-            None => {
-                if let Some(listener) = slot.as_mut() {
-                    listener.stop();
+        let bindings = shortcuts.all_bindings();
+        // Dropping a listener stops it
+        self.shortcut_listeners.truncate(bindings.len());
+
+        for (index, binding) in bindings.iter().enumerate() {
+            let kind = listener_kind(binding);
+            match self.shortcut_listeners.get_mut(index) {
+                Some(listener) if listener.matches(&binding.keys, kind) => {}
+                Some(listener) => {
+                    listener.restart(binding.keys.clone(), kind, index, self.shortcut_tx.clone())?
                 }
-                *slot = None;
+                None => self.shortcut_listeners.push(ShortcutListener::spawn(
+                    binding.keys.clone(),
+                    kind,
+                    index,
+                    self.shortcut_tx.clone(),
+                )?),
             }
         }
 
+        self.shortcut_bindings = bindings;
         Ok(())
     }
 
     fn apply_config_update(&mut self, new_config: Config) -> Result<()> {
         tracing::debug!(?new_config, "Apply config update requested");
-        if new_config == self.current_config {
+        if new_config == self.base_config {
             tracing::debug!("Config unchanged; ignoring update");
             return Ok(());
         }
 
-        if self.recording_session.is_some() || self.is_processing {
+        if self.recording_session.is_some() || self.is_processing() {
             warn!("Skipping config refresh while busy");
             return Ok(());
         }
 
-        let assets_dir = self.config_manager.get_assets_dir();
-        let audio_feedback = AudioFeedback::new(
-            new_config.audio_feedback,
-            assets_dir,
-            new_config.start_sound_path.clone(),
-            new_config.stop_sound_path.clone(),
-            new_config.start_sound_volume,
-            new_config.stop_sound_volume,
-        );
-
-        let text_injector = TextInjector::new(
-            new_config.shift_paste,
-            new_config.global_paste_shortcut,
-            new_config.paste_hints.shift.clone(),
-            new_config.word_overrides.clone(),
-            new_config.auto_copy_clipboard,
+        // Editing `active_profile` in the file wins over a runtime switch
+        let active_profile = if new_config.active_profile != self.base_config.active_profile {
+            new_config.active_profile.clone()
+        } else {
+            self.active_profile
+                .clone()
+                .filter(|name| new_config.profile(name).is_some())
+        };
+        let window_profile = self
+            .window_profile
+            .clone()
+            .filter(|name| new_config.profile(name).is_some());
+        let shortcut_profile = self
+            .shortcut_profile
+            .clone()
+            .filter(|name| new_config.profile(name).is_some());
+        let effective = layered_config(
+            &new_config,
+            [
+                active_profile.as_deref(),
+                window_profile.as_deref(),
+                shortcut_profile.as_deref(),
+            ],
         )?;
+        self.reconfigure(effective)?;
+        self.base_config = new_config;
+        self.window_profile = window_profile;
+        self.shortcut_profile = shortcut_profile;
+        let profile_changed = active_profile != self.active_profile;
+        self.active_profile = active_profile;
+
+        info!("Configuration updated");
+        self.emit(DaemonEvent::ConfigReloaded {
+            provider: self.transcriber.provider().label().to_string(),
+        });
+        if profile_changed {
+            self.emit(DaemonEvent::ProfileChanged {
+                profile: self.active_profile.clone(),
+            });
+            self.emit_settled();
+        }
+        tracing::debug!(?self.current_config, "Config state after update");
+        Ok(())
+    }
+
+    /// Rebuild the components whose settings differ between `current_config` and `new_config`
+    fn reconfigure(&mut self, new_config: Config) -> Result<()> {
+        if new_config == self.current_config {
+            return Ok(());
+        }
+
+        let audio_feedback =
+            audio_feedback_needs_refresh(&self.current_config, &new_config).then(|| {
+                AudioFeedback::new(
+                    new_config.audio_feedback,
+                    self.config_manager.get_assets_dir(),
+                    new_config.start_sound_path.clone(),
+                    new_config.stop_sound_path.clone(),
+                    new_config.start_sound_volume,
+                    new_config.stop_sound_volume,
+                )
+            });
+
+        let text_injector = if injector_needs_refresh(&self.current_config, &new_config) {
+            let speech_replacements =
+                SpeechReplacements::load(&new_config, &self.config_manager.get_speech_packs_dir())
+                    .context("Failed to load speech replacements")?;
+            Some(
+                TextInjector::new(&new_config, speech_replacements)?
+                    .with_undo_state(self.undo_state.clone()),
+            )
+        } else {
+            None
+        };
 
         let transcriber_changed =
-            TranscriptionBackend::needs_refresh(&self.current_config, &new_config);
+            TranscriptionChain::needs_refresh(&self.current_config, &new_config);
+
+        let postprocessor = if new_config.llm_postprocess != self.current_config.llm_postprocess {
+            let postprocessor = LlmPostProcessor::new(&new_config.llm_postprocess)
+                .context("Failed to configure LLM post-processing")?;
+            if postprocessor.is_none() {
+                info!("✨ LLM post-processing disabled");
+            }
+            Some(postprocessor.map(Arc::new))
+        } else {
+            None
+        };
 
         if self.current_config.audio_device != new_config.audio_device {
             self.audio_capture
@@ -393,8 +775,8 @@ impl HyprwhsprApp {
 
         if transcriber_changed {
             let vad_options = build_vad_options(&self.config_manager, &new_config);
-            let backend =
-                TranscriptionBackend::build(&self.config_manager, &new_config, vad_options)
+            let mut backend =
+                TranscriptionChain::build(&self.config_manager, &new_config, vad_options)
                     .context("Failed to reconfigure transcription backend")?;
             backend
                 .initialize()
@@ -403,12 +785,11 @@ impl HyprwhsprApp {
                 "🎯 Active transcription backend: {}",
                 backend.provider().label()
             );
-            self.transcriber = backend;
+            self.transcriber = Arc::new(backend);
         }
 
         let shortcuts_changed = new_config.shortcuts != self.current_config.shortcuts
-            || self.press_listener.is_none()
-            || (new_config.hold_shortcut().is_some() && self.hold_listener.is_none());
+            || self.shortcut_listeners.is_empty();
 
         if shortcuts_changed {
             self.ensure_shortcut_listeners(new_config.shortcuts.clone())?;
@@ -448,60 +829,171 @@ impl HyprwhsprApp {
             }
         }
 
-        self.text_injector = Arc::new(Mutex::new(text_injector));
-        self.audio_feedback = audio_feedback;
+        if let Some(text_injector) = text_injector {
+            self.text_injector = Arc::new(Mutex::new(text_injector));
+        }
+        if let Some(postprocessor) = postprocessor {
+            self.postprocessor = postprocessor;
+        }
+        if let Some(audio_feedback) = audio_feedback {
+            self.audio_feedback = audio_feedback;
+        }
         self.current_config = new_config;
+        Ok(())
+    }
 
-        info!("Configuration updated");
-        tracing::debug!(?self.current_config, "Config state after update");
+    /// Switch to the profiles for a new recording: the one matching the focused window and
+    /// the one of the shortcut that started it. Failures are logged and leave the current
+    /// settings in place.
+    async fn select_recording_profiles(&mut self, shortcut_profile: Option<String>) {
+        let window = if self.base_config.has_window_profiles() {
+            focused_window().await.unwrap_or_default()
+        } else {
+            ActiveWindow::default()
+        };
+        let window_profile = self
+            .base_config
+            .window_profile(window.class.as_deref(), window.title.as_deref())
+            .map(|profile| profile.name.clone());
+        if window_profile == self.window_profile && shortcut_profile == self.shortcut_profile {
+            return;
+        }
+
+        let config = layered_config(
+            &self.base_config,
+            [
+                self.active_profile.as_deref(),
+                window_profile.as_deref(),
+                shortcut_profile.as_deref(),
+            ],
+        );
+        if let Err(err) = config.and_then(|config| self.reconfigure(config)) {
+            warn!("Failed to apply the profiles for this recording: {:#}", err);
+            return;
+        }
+
+        if window_profile != self.window_profile {
+            match &window_profile {
+                Some(name) => info!(
+                    "🪟 Profile \"{}\" active for {}",
+                    name,
+                    window.class.as_deref().unwrap_or("the focused window")
+                ),
+                None => info!("🪟 No profile matches the focused window"),
+            }
+        }
+        if let Some(name) = shortcut_profile
+            .as_deref()
+            .filter(|_| shortcut_profile != self.shortcut_profile)
+        {
+            info!("⌨️  Profile \"{}\" active for this shortcut", name);
+        }
+        self.window_profile = window_profile;
+        self.shortcut_profile = shortcut_profile;
+    }
+
+    /// Select a profile by name, or clear the selection with `None`
+    fn set_active_profile(&mut self, profile: Option<String>) -> Result<()> {
+        if self.recording_session.is_some() {
+            bail!("Cannot switch profiles while recording");
+        }
+        if let Some(name) = &profile {
+            if self.base_config.profile(name).is_none() {
+                bail!("Unknown profile: {}", name);
+            }
+        }
+        if profile == self.active_profile {
+            return Ok(());
+        }
+
+        let config = layered_config(
+            &self.base_config,
+            [
+                profile.as_deref(),
+                self.window_profile.as_deref(),
+                self.shortcut_profile.as_deref(),
+            ],
+        )?;
+        self.reconfigure(config)?;
+        self.active_profile = profile;
+
+        match &self.active_profile {
+            Some(name) => info!("🎚️ Profile \"{}\" selected", name),
+            None => info!("🎚️ Profile cleared; using the base config"),
+        }
+        self.emit(DaemonEvent::ProfileChanged {
+            profile: self.active_profile.clone(),
+        });
+        self.emit_settled();
         Ok(())
     }
 
+    fn control_set_profile(&mut self, profile: Option<String>) -> ControlResponse {
+        match self.set_active_profile(profile) {
+            Ok(()) => ControlResponse::success(match &self.active_profile {
+                Some(name) => format!("Profile: {}", name),
+                None => "No profile".to_string(),
+            }),
+            Err(err) => ControlResponse::failure(format!("{:#}", err)),
+        }
+    }
+
     fn log_shortcut_configuration(&self, shortcuts: &ShortcutsConfig) {
-        match shortcuts.press.as_deref() {
-            Some(value) => info!("Press shortcut active: {}", value),
-            None => info!("Press shortcut disabled"),
+        for binding in shortcuts.all_bindings() {
+            match &binding.profile {
+                Some(profile) => info!(
+                    "{} shortcut active: {} (profile \"{}\")",
+                    binding_label(&binding),
+                    binding.keys,
+                    profile
+                ),
+                None => info!(
+                    "{} shortcut active: {}",
+                    binding_label(&binding),
+                    binding.keys
+                ),
+            }
         }
 
-        match shortcuts.hold.as_deref() {
-            Some(value) => info!("Hold shortcut active: {}", value),
-            None => info!("Hold shortcut disabled"),
+        if shortcuts.hold.is_none() {
+            info!("Hold shortcut disabled");
         }
     }
 
     async fn handle_shortcut(&mut self, event: ShortcutEvent) -> Result<()> {
-        match (event.kind, event.phase) {
-            (ShortcutKind::Press, ShortcutPhase::Start) => {
-                if self.is_processing {
-                    warn!("Still processing previous recording, ignoring shortcut");
-                    return Ok(());
-                }
+        let Some(binding) = self.shortcut_bindings.get(event.binding) else {
+            debug!("Shortcut ignored (binding {} was removed)", event.binding);
+            return Ok(());
+        };
+        let trigger = RecordingTrigger::Shortcut(event.binding);
 
+        match (binding.action, event.kind, event.phase) {
+            (ShortcutAction::Cancel, _, ShortcutPhase::Start) => {
+                let cancelled = self.cancel()?;
+                if !cancelled {
+                    debug!("Cancel shortcut ignored (nothing to cancel)");
+                }
+            }
+            (ShortcutAction::Reinject, _, ShortcutPhase::Start) => self.reinject_last().await?,
+            (_, ShortcutKind::Press, ShortcutPhase::Start) => {
                 if self.recording_session.is_some() {
-                    self.stop_recording(event.triggered_at).await?;
-                } else {
-                    self.start_recording(RecordingTrigger::PressShortcut, event.triggered_at)
+                    self.stop_recording(event.triggered_at, StopReason::Manual)
                         .await?;
+                } else {
+                    self.start_recording(trigger, event.triggered_at).await?;
                 }
             }
-            (ShortcutKind::Hold, ShortcutPhase::Start) => {
-                if self.is_processing {
-                    warn!("Still processing previous recording, ignoring hold shortcut");
-                    return Ok(());
-                }
-
+            (_, ShortcutKind::Hold, ShortcutPhase::Start) => {
                 if self.recording_session.is_some() {
                     debug!("Hold shortcut ignored because recording is already active");
                 } else {
-                    self.start_recording(RecordingTrigger::HoldShortcut, event.triggered_at)
-                        .await?;
+                    self.start_recording(trigger, event.triggered_at).await?;
                 }
             }
-            (ShortcutKind::Hold, ShortcutPhase::End) => {
-                if matches!(self.recording_trigger, Some(RecordingTrigger::HoldShortcut))
-                    && self.recording_session.is_some()
-                {
-                    self.stop_recording(event.triggered_at).await?;
+            (_, ShortcutKind::Hold, ShortcutPhase::End) => {
+                if self.recording_trigger == Some(trigger) && self.recording_session.is_some() {
+                    self.stop_recording(event.triggered_at, StopReason::Manual)
+                        .await?;
                 } else {
                     debug!("Hold release ignored (no active hold-triggered recording)");
                 }
@@ -512,6 +1004,101 @@ impl HyprwhsprApp {
         Ok(())
     }
 
+    /// Paste the newest transcription from the history again
+    async fn reinject_last(&mut self) -> Result<()> {
+        if self.recording_session.is_some() || self.is_processing() {
+            debug!("Re-inject shortcut ignored while busy");
+            return Ok(());
+        }
+        let Some(text) = self.emitter.status_writer.last_transcription() else {
+            info!("Nothing to re-inject; the transcription history is empty");
+            return Ok(());
+        };
+
+        info!("🔁 Re-injecting the last transcription");
+        let injected = self.text_injector.lock().await.inject_text(&text).await;
+        match injected {
+            Ok(()) => {
+                self.emit(DaemonEvent::Injected);
+                Ok(())
+            }
+            Err(err) => {
+                self.emit(DaemonEvent::InjectionFailed {
+                    message: format!("{:#}", err),
+                });
+                Err(err)
+            }
+        }
+    }
+
+    async fn handle_control(&mut self, command: ControlCommand) -> ControlResponse {
+        let triggered_at = Instant::now();
+        debug!(?command, "Control command received");
+
+        let result = match command {
+            ControlCommand::Status => return self.control_status(),
+            ControlCommand::Subscribe => {
+                return ControlResponse::failure("Subscriptions are served by the control socket")
+            }
+            ControlCommand::Toggle if self.recording_session.is_some() => self
+                .stop_recording(triggered_at, StopReason::Manual)
+                .await
+                .map(|_| "Recording stopped"),
+            ControlCommand::Toggle | ControlCommand::Start => {
+                if self.recording_session.is_some() {
+                    return ControlResponse::failure("Already recording");
+                }
+                self.start_recording(RecordingTrigger::Control, triggered_at)
+                    .await
+                    .map(|_| "Recording started")
+            }
+            ControlCommand::Stop => {
+                if self.recording_session.is_none() {
+                    return ControlResponse::failure("Not recording");
+                }
+                self.stop_recording(triggered_at, StopReason::Manual)
+                    .await
+                    .map(|_| "Recording stopped")
+            }
+            ControlCommand::NextProfile | ControlCommand::PreviousProfile => {
+                let forward = command == ControlCommand::NextProfile;
+                let profile = self
+                    .base_config
+                    .cycle_profile(self.active_profile.as_deref(), forward)
+                    .map(|profile| profile.name.clone());
+                return self.control_set_profile(profile);
+            }
+            ControlCommand::SetProfile(profile) => return self.control_set_profile(profile),
+            ControlCommand::Cancel => match self.cancel() {
+                Ok(true) => Ok("Cancelled"),
+                Ok(false) => return ControlResponse::failure("Nothing to cancel"),
+                Err(err) => Err(err),
+            },
+        };
+
+        match result {
+            Ok(message) => ControlResponse::success(message),
+            Err(err) => ControlResponse::failure(format!("{:#}", err)),
+        }
+    }
+
+    fn control_status(&self) -> ControlResponse {
+        let state = if self.recording_session.is_some() {
+            DaemonState::Recording
+        } else if self.is_processing() {
+            DaemonState::Processing
+        } else {
+            DaemonState::Idle
+        };
+
+        ControlResponse::success("").with_status(DaemonStatus {
+            state,
+            provider: self.transcriber.provider().label().to_string(),
+            queued: self.pending_jobs.len(),
+            profile: self.active_profile.clone(),
+        })
+    }
+
     async fn start_recording(
         &mut self,
         trigger: RecordingTrigger,
@@ -519,6 +1106,15 @@ impl HyprwhsprApp {
     ) -> Result<()> {
         info!("🎤 Starting recording...");
 
+        let binding = match trigger {
+            RecordingTrigger::Shortcut(index) => self.shortcut_bindings.get(index).cloned(),
+            RecordingTrigger::Control => None,
+        };
+        let action = binding
+            .as_ref()
+            .map(|binding| binding.action)
+            .unwrap_or_default();
+
         self.audio_feedback.play_start_sound()?;
 
         let session = self
@@ -526,22 +1122,41 @@ impl HyprwhsprApp {
             .start_recording()
             .context("Failed to start recording")?;
 
+        let recording_started_at = Instant::now();
+        let sample_rate = session.sample_rate();
         self.recording_session = Some(session);
         self.recording_trigger = Some(trigger);
+        self.recording_id += 1;
+
+        // Capture is already running, so switching profiles loses no speech. The monitor
+        // reads the streaming and duration settings of the profiles, so it comes after.
+        self.select_recording_profiles(binding.and_then(|binding| binding.profile))
+            .await;
+        self.live_monitor = self.build_live_monitor(trigger, sample_rate, recording_started_at);
+        self.recording_task =
+            if action == ShortcutAction::Translate || self.current_config.transcription.translate {
+                info!("🌐 This recording will be translated to English");
+                TranscriptionTask::Translate
+            } else {
+                TranscriptionTask::Transcribe
+            };
+        self.recording_commands_only = action == ShortcutAction::Command;
+        if self.recording_commands_only {
+            info!("🗣️  This recording only runs voice commands");
+        }
 
-        let recording_started_at = Instant::now();
         self.benchmark = Some(BenchmarkRecorder::new(
             self.transcriber.provider().label().to_string(),
             triggered_at,
             recording_started_at,
         ));
 
-        self.status_writer.set_recording(true)?;
+        self.emit(DaemonEvent::RecordingStarted);
 
         Ok(())
     }
 
-    async fn stop_recording(&mut self, triggered_at: Instant) -> Result<()> {
+    async fn stop_recording(&mut self, triggered_at: Instant, reason: StopReason) -> Result<()> {
         info!("🛑 Stopping recording...");
 
         let session = self
@@ -551,47 +1166,362 @@ impl HyprwhsprApp {
 
         if let Some(benchmark) = self.benchmark.as_mut() {
             benchmark.mark_keybind_stop(triggered_at);
+            if reason == StopReason::MaxDuration {
+                benchmark.mark_forced_stop();
+            }
         }
 
         self.audio_feedback.play_stop_sound()?;
 
-        self.status_writer.set_processing()?;
-
         let captured_audio = session.stop().context("Failed to stop recording")?;
         let stop_timestamp = Instant::now();
         self.recording_trigger = None;
+        let stream = self.live_monitor.take().and_then(|monitor| monitor.stream);
+
+        self.emit(DaemonEvent::RecordingStopped {
+            duration_ms: captured_audio.duration().as_millis() as u64,
+            reason,
+        });
 
         if let Some(benchmark) = self.benchmark.as_mut() {
             benchmark.mark_recording_stop(stop_timestamp);
             benchmark.record_original_audio(captured_audio.len(), captured_audio.sample_rate);
         }
 
-        if !captured_audio.is_empty() {
-            self.is_processing = true;
-            if let Err(e) = self.process_audio(captured_audio).await {
-                error!("❌ Error processing audio: {:#}", e);
-                self.status_writer
-                    .set_error(&format!("{:#}", e))
-                    .unwrap_or_else(|e| tracing::warn!("Failed to set error status: {}", e));
-                warn!("Failed to process recording. Check logs for details.");
+        // Streamed chunks already covered everything before `chunk_start`
+        let (captured_audio, continues_text) = match stream {
+            Some(stream) => {
+                let CapturedAudio {
+                    mut samples,
+                    sample_rate,
+                } = captured_audio;
+                samples.drain(..stream.chunk_start.min(samples.len()));
+                (
+                    CapturedAudio {
+                        samples,
+                        sample_rate,
+                    },
+                    stream.chunks_sent > 0,
+                )
+            }
+            None => (captured_audio, false),
+        };
+
+        if captured_audio.is_empty() {
+            if !continues_text {
+                warn!("No audio data captured");
             }
             self.benchmark = None;
-            self.is_processing = false;
-            // Return to inactive state after processing
-            self.status_writer
-                .set_state(WaybarState::Inactive, "Ready")
-                .unwrap_or_else(|e| tracing::warn!("Failed to set inactive status: {}", e));
+            self.emit_settled();
+            return Ok(());
+        }
+
+        self.emit(DaemonEvent::Processing);
+
+        match self.prepare_audio(captured_audio) {
+            Ok(Some(audio)) => {
+                let mut job = self.transcription_job(audio);
+                job.benchmark = self.benchmark.take();
+                job.continues_text = continues_text;
+                self.enqueue_transcription(job);
+            }
+            Ok(None) => self.emit_settled(),
+            Err(e) => {
+                error!("❌ Error processing audio: {:#}", e);
+                self.benchmark = None;
+                self.emit(DaemonEvent::Error {
+                    message: format!("{:#}", e),
+                });
+                self.emit_settled();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Report the steady state once a recording or job has been dealt with.
+    fn emit_settled(&self) {
+        if let Some(event) = settled_event(self.recording_session.is_some(), self.is_processing()) {
+            self.emit(event);
+        }
+    }
+
+    fn build_live_monitor(
+        &self,
+        trigger: RecordingTrigger,
+        sample_rate: u32,
+        started_at: Instant,
+    ) -> Option<LiveMonitor> {
+        let config = &self.current_config;
+        let fast_vad = &config.fast_vad;
+
+        // Hold recordings end on key release; only press-style ones wait for silence
+        let detector_rate = if FastVad::supports_sample_rate(sample_rate) {
+            sample_rate
         } else {
-            warn!("No audio data captured");
-            self.benchmark = None;
-            self.status_writer
-                .set_state(WaybarState::Inactive, "Ready")
-                .unwrap_or_else(|e| tracing::warn!("Failed to set inactive status: {}", e));
+            16_000
+        };
+        let held = match trigger {
+            RecordingTrigger::Shortcut(index) => self
+                .shortcut_bindings
+                .get(index)
+                .is_some_and(|binding| listener_kind(binding) == ShortcutKind::Hold),
+            RecordingTrigger::Control => false,
+        };
+        let end_of_speech = if fast_vad.auto_stop && !held {
+            match EndOfSpeechDetector::new(fast_vad, detector_rate) {
+                Ok(detector) => Some(detector),
+                Err(err) => {
+                    warn!("Auto-stop unavailable for this recording: {:#}", err);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let streaming = &config.streaming;
+        let stream = if streaming.enabled {
+            match EndOfSpeechDetector::with_silence_ms(fast_vad, detector_rate, streaming.pause_ms)
+            {
+                Ok(pauses) => Some(ChunkStream {
+                    pauses,
+                    chunk_start: 0,
+                    min_chunk_samples: (sample_rate as u64 * streaming.min_chunk_ms as u64 / 1000)
+                        as usize,
+                    chunks_sent: 0,
+                }),
+                Err(err) => {
+                    warn!("Streaming unavailable for this recording: {:#}", err);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let max_secs = config.max_recording_secs;
+        let deadline = (max_secs > 0).then(|| started_at + Duration::from_secs(max_secs.into()));
+        let warning_secs = config.max_recording_warning_secs;
+        let warn_at = deadline
+            .filter(|_| warning_secs > 0 && warning_secs < max_secs)
+            .map(|deadline| deadline - Duration::from_secs(warning_secs.into()));
+
+        if end_of_speech.is_none() && stream.is_none() && deadline.is_none() {
+            return None;
+        }
+        Some(LiveMonitor {
+            offset: 0,
+            end_of_speech,
+            deadline,
+            warn_at,
+            stream,
+        })
+    }
+
+    async fn monitor_recording(&mut self) -> Result<()> {
+        if self.recording_session.is_none() {
+            return Ok(());
+        }
+        let Some(monitor) = self.live_monitor.as_mut() else {
+            return Ok(());
+        };
+
+        let now = Instant::now();
+        if monitor.deadline.is_some_and(|deadline| now >= deadline) {
+            info!(
+                "⏱️ Recording reached the {}s limit; stopping",
+                self.current_config.max_recording_secs
+            );
+            return self.stop_recording(now, StopReason::MaxDuration).await;
+        }
+
+        if monitor.warn_at.is_some_and(|warn_at| now >= warn_at) {
+            monitor.warn_at = None;
+            let remaining_secs = monitor
+                .deadline
+                .map(|deadline| deadline.saturating_duration_since(now).as_secs_f32().ceil() as u32)
+                .unwrap_or_default();
+            info!("⏱️ Recording stops automatically in {}s", remaining_secs);
+            self.audio_feedback.play_warning_sound()?;
+            self.emit(DaemonEvent::RecordingLimitApproaching { remaining_secs });
+        }
+
+        let (Some(session), Some(monitor)) =
+            (self.recording_session.as_ref(), self.live_monitor.as_mut())
+        else {
+            return Ok(());
+        };
+
+        if monitor.end_of_speech.is_none() && monitor.stream.is_none() {
+            return Ok(());
+        }
+
+        let samples = session.samples_since(monitor.offset);
+        if samples.is_empty() {
+            return Ok(());
+        }
+        monitor.offset += samples.len();
+        let sample_rate = session.sample_rate();
+
+        // Both detectors run at the same rate
+        let detector_rate = monitor
+            .end_of_speech
+            .as_ref()
+            .map(EndOfSpeechDetector::sample_rate_hz)
+            .or_else(|| monitor.stream.as_ref().map(|s| s.pauses.sample_rate_hz()))
+            .unwrap_or(sample_rate);
+        let samples = if sample_rate == detector_rate {
+            samples
+        } else {
+            resample_audio(&samples, sample_rate, detector_rate)
+        };
+
+        let mut speech_ended = false;
+        if let Some(detector) = monitor.end_of_speech.as_mut() {
+            match detector.push(&samples) {
+                Ok(ended) => speech_ended = ended,
+                Err(err) => {
+                    warn!("Disabling auto-stop for this recording: {:#}", err);
+                    monitor.end_of_speech = None;
+                }
+            }
+        }
+
+        let mut chunk_end = None;
+        if let Some(stream) = monitor.stream.as_mut() {
+            match stream.pauses.push(&samples) {
+                Ok(true) => {
+                    stream.pauses.reset();
+                    if monitor.offset - stream.chunk_start >= stream.min_chunk_samples {
+                        chunk_end = Some(monitor.offset);
+                    }
+                }
+                Ok(false) => {}
+                Err(err) => {
+                    warn!("Disabling streaming for this recording: {:#}", err);
+                    monitor.stream = None;
+                }
+            }
+        }
+
+        if speech_ended {
+            info!("🤫 Trailing silence detected; stopping recording");
+            self.stop_recording(Instant::now(), StopReason::Silence)
+                .await?;
+        } else if let Some(end) = chunk_end {
+            self.stream_chunk(end);
         }
 
         Ok(())
     }
 
+    /// Hand the audio up to `end` to the transcription queue while the recording continues.
+    fn stream_chunk(&mut self, end: usize) {
+        let (Some(session), Some(stream)) = (
+            self.recording_session.as_ref(),
+            self.live_monitor
+                .as_mut()
+                .and_then(|monitor| monitor.stream.as_mut()),
+        ) else {
+            return;
+        };
+
+        let mut samples = session.samples_since(stream.chunk_start);
+        samples.truncate(end - stream.chunk_start);
+        stream.chunk_start = end;
+        let continues_text = stream.chunks_sent > 0;
+        let chunk = CapturedAudio {
+            samples,
+            sample_rate: session.sample_rate(),
+        };
+        let chunk_secs = chunk.duration().as_secs_f32();
+
+        match self.prepare_chunk(chunk) {
+            Ok(Some(audio)) => {
+                info!(
+                    "🌊 Streaming {:.1}s chunk while recording continues",
+                    chunk_secs
+                );
+                if let Some(stream) = self
+                    .live_monitor
+                    .as_mut()
+                    .and_then(|monitor| monitor.stream.as_mut())
+                {
+                    stream.chunks_sent += 1;
+                }
+                let mut job = self.transcription_job(audio);
+                job.continues_text = continues_text;
+                self.enqueue_transcription(job);
+            }
+            Ok(None) => debug!("Streamed chunk held no speech; skipping"),
+            Err(e) => {
+                error!("❌ Error processing streamed chunk: {:#}", e);
+                self.emit(DaemonEvent::Error {
+                    message: format!("{:#}", e),
+                });
+            }
+        }
+    }
+
+    /// Discard the most recent work: the active recording, else the newest queued
+    /// transcription, else the one in flight, along with every other job of the same
+    /// recording. Returns false when there was nothing to cancel.
+    fn cancel(&mut self) -> Result<bool> {
+        let (stage, recording) = if self.recording_session.take().is_some() {
+            info!("🚫 Cancelling recording...");
+
+            // Dropping the session stops the stream and discards the buffered audio
+            self.recording_trigger = None;
+            self.live_monitor = None;
+            self.benchmark = None;
+
+            self.audio_feedback.play_stop_sound()?;
+            (DaemonState::Recording, self.recording_id)
+        } else if let Some(job) = self.pending_jobs.pop_back() {
+            info!("🚫 Dropping queued transcription...");
+            (DaemonState::Processing, job.recording)
+        } else if let Some(job) = self.active_job.take() {
+            info!("🚫 Cancelling transcription...");
+
+            // Aborting drops the transcribe future, which kills any whisper-cli child
+            job.handle.abort();
+            (DaemonState::Processing, job.recording)
+        } else {
+            return Ok(false);
+        };
+        self.discard_recording_jobs(recording);
+
+        for event in cancel_events(
+            stage,
+            self.recording_session.is_some(),
+            self.is_processing(),
+        ) {
+            self.emit(event);
+        }
+        Ok(true)
+    }
+
+    /// Drop the queued and running jobs of a cancelled recording, such as the chunks it
+    /// streamed before the cancel
+    fn discard_recording_jobs(&mut self, recording: u64) {
+        let queued = self.pending_jobs.len();
+        self.pending_jobs.retain(|job| job.recording != recording);
+        let dropped = queued - self.pending_jobs.len();
+        if dropped > 0 {
+            info!(
+                "🚫 Dropped {} queued chunk(s) of the cancelled recording",
+                dropped
+            );
+        }
+
+        if let Some(job) = self.active_job.take_if(|job| job.recording == recording) {
+            info!("🚫 Cancelling the running chunk of the cancelled recording");
+            job.handle.abort();
+            self.start_next_transcription();
+        }
+    }
+
     fn preprocess_audio(&mut self, audio_data: CapturedAudio) -> Result<Option<PreprocessedAudio>> {
         let CapturedAudio {
             mut samples,
@@ -663,7 +1593,9 @@ impl HyprwhsprApp {
         }))
     }
 
-    async fn process_audio(&mut self, audio_data: CapturedAudio) -> Result<()> {
+    /// Trim and resample captured audio for the backend.
+    /// Returns `None` when nothing is left worth transcribing.
+    fn prepare_audio(&mut self, audio_data: CapturedAudio) -> Result<Option<Vec<f32>>> {
         if let Some(benchmark) = self.benchmark.as_mut() {
             benchmark.mark_processing_start(Instant::now());
         }
@@ -677,24 +1609,14 @@ impl HyprwhsprApp {
         }
 
         let Some(preprocessed) = maybe_audio else {
-            if let Some(mut benchmark) = self.benchmark.take() {
-                benchmark.mark_injection_skipped(Instant::now());
-                if let Some(summary) = benchmark.finalize() {
-                    info!(message = %format_args!("\n{}", summary));
-                }
-            }
-            return Ok(());
+            finish_skipped(self.benchmark.take());
+            return Ok(None);
         };
 
         if preprocessed.audio.is_empty() {
             info!("🎧 No audio remaining after preprocessing; skipping transcription");
-            if let Some(mut benchmark) = self.benchmark.take() {
-                benchmark.mark_injection_skipped(Instant::now());
-                if let Some(summary) = benchmark.finalize() {
-                    info!(message = %format_args!("\n{}", summary));
-                }
-            }
-            return Ok(());
+            finish_skipped(self.benchmark.take());
+            return Ok(None);
         }
 
         let PreprocessedAudio { audio, report } = preprocessed;
@@ -727,54 +1649,112 @@ impl HyprwhsprApp {
             benchmark.record_audio_sent(audio_for_transcription.len(), 16_000);
         }
 
-        let TranscriptionResult { text, metrics } =
-            self.transcriber.transcribe(audio_for_transcription).await?;
+        Ok(Some(audio_for_transcription))
+    }
 
-        if let Some(benchmark) = self.benchmark.as_mut() {
-            benchmark.record_backend_metrics(metrics);
+    /// Like [`Self::prepare_audio`] for a streamed chunk, which has no benchmark of its own.
+    fn prepare_chunk(&mut self, chunk: CapturedAudio) -> Result<Option<Vec<f32>>> {
+        let Some(PreprocessedAudio { audio, .. }) = self.preprocess_audio(chunk)? else {
+            return Ok(None);
+        };
+        if audio.is_empty() {
+            return Ok(None);
         }
 
-        if text.trim().is_empty() {
-            warn!("Empty transcription, nothing to inject");
-            if let Some(mut benchmark) = self.benchmark.take() {
-                benchmark.mark_injection_skipped(Instant::now());
-                if let Some(summary) = benchmark.finalize() {
-                    info!(message = %format_args!("\n{}", summary));
-                }
-            }
-            return Ok(());
-        }
+        Ok(Some(if audio.sample_rate == 16_000 {
+            audio.samples
+        } else {
+            resample_audio(&audio.samples, audio.sample_rate, 16_000)
+        }))
+    }
 
-        info!("📝 Transcription: \"{}\"", text);
+    fn transcription_job(&self, audio: Vec<f32>) -> TranscriptionJob {
+        TranscriptionJob {
+            recording: self.recording_id,
+            audio,
+            transcriber: Arc::clone(&self.transcriber),
+            postprocessor: self.postprocessor.clone(),
+            text_injector: Arc::clone(&self.text_injector),
+            emitter: self.emitter.clone(),
+            benchmark: None,
+            continues_text: false,
+            task: self.recording_task,
+            commands_only: self.recording_commands_only,
+        }
+    }
 
-        // Save to history for Walker/Elephant integration
-        if let Err(e) = self.status_writer.save_transcription(&text) {
-            tracing::warn!("Failed to save transcription to history: {}", e);
+    /// Queue a recording for transcription. Jobs run one at a time in recording
+    /// order, so their text is injected in the order it was spoken.
+    fn enqueue_transcription(&mut self, job: TranscriptionJob) {
+        self.pending_jobs.push_back(job);
+        if self.active_job.is_none() {
+            self.start_next_transcription();
+        } else {
+            info!(
+                "⏳ Queued recording behind the running transcription ({} waiting)",
+                self.pending_jobs.len()
+            );
         }
+    }
 
-        let text_injector = Arc::clone(&self.text_injector);
-        let mut injector = text_injector.lock().await;
+    /// Run the next queued job on a task of its own so the loop stays
+    /// responsive and the job can be aborted by a cancel.
+    fn start_next_transcription(&mut self) {
+        let Some(job) = self.pending_jobs.pop_front() else {
+            return;
+        };
 
-        let injection_start = Instant::now();
-        if let Some(benchmark) = self.benchmark.as_mut() {
-            benchmark.mark_injection_start(injection_start);
-        }
+        let id = self.next_job_id;
+        self.next_job_id += 1;
+        let recording = job.recording;
 
-        debug!("⌨️  Injecting text into active application...");
-        injector.inject_text(&text).await?;
+        let done_tx = self.job_tx.clone();
+        let handle = tokio::spawn(async move {
+            let result = job.run().await;
+            let _ = done_tx.send(JobOutcome { id, result }).await;
+        });
 
-        let injection_end = Instant::now();
-        if let Some(benchmark) = self.benchmark.as_mut() {
-            benchmark.mark_injection_end(injection_end);
-        }
+        self.active_job = Some(ActiveJob {
+            id,
+            recording,
+            handle,
+        });
+    }
 
-        if let Some(benchmark) = self.benchmark.take() {
-            if let Some(summary) = benchmark.finalize() {
-                info!(message = %format_args!("\n{}", summary));
-            }
+    fn finish_transcription(&mut self, outcome: JobOutcome) {
+        if self.active_job.as_ref().map(|job| job.id) != Some(outcome.id) {
+            debug!(
+                job = outcome.id,
+                "Ignoring result of a cancelled transcription"
+            );
+            return;
+        }
+        self.active_job = None;
+
+        if let Err(e) = outcome.result {
+            error!("❌ Error processing audio: {:#}", e);
+            self.emit(DaemonEvent::Error {
+                message: format!("{:#}", e),
+            });
+            warn!("Failed to process recording. Check logs for details.");
         }
 
-        Ok(())
+        self.start_next_transcription();
+        // Return to inactive state once the queue has drained
+        self.emit_settled();
+    }
+
+    /// Stream of daemon state changes, also served on the control socket.
+    pub fn events(&self) -> &EventBus {
+        &self.emitter.events
+    }
+
+    fn emit(&self, event: DaemonEvent) {
+        self.emitter.emit(event);
+    }
+
+    fn is_processing(&self) -> bool {
+        self.active_job.is_some()
     }
 
     pub async fn cleanup(&mut self) -> Result<()> {
@@ -783,20 +1763,113 @@ impl HyprwhsprApp {
         if self.recording_session.is_some() {
             self.recording_session = None;
         }
-        self.status_writer.cleanup()?;
-
-        if let Some(listener) = &mut self.press_listener {
-            listener.stop();
+        self.pending_jobs.clear();
+        if let Some(job) = self.active_job.take() {
+            job.handle.abort();
         }
-        self.press_listener = None;
+        self.emitter.status_writer.cleanup()?;
 
-        if let Some(listener) = &mut self.hold_listener {
+        for listener in &mut self.shortcut_listeners {
             listener.stop();
         }
-        self.hold_listener = None;
+        self.shortcut_listeners.clear();
         self.recording_trigger = None;
+        self.live_monitor = None;
 
         info!("✅ Cleanup completed");
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel_settles_back_to_ready_or_processing() {
+        let cancelled = |stage| DaemonEvent::Cancelled { stage };
+
+        // Recording discarded with nothing queued, or with a transcription still running
+        assert_eq!(
+            cancel_events(DaemonState::Recording, false, false),
+            [cancelled(DaemonState::Recording), DaemonEvent::Ready]
+        );
+        assert_eq!(
+            cancel_events(DaemonState::Recording, false, true),
+            [cancelled(DaemonState::Recording), DaemonEvent::Processing]
+        );
+
+        // Queued job dropped behind a running one, then the running one aborted
+        assert_eq!(
+            cancel_events(DaemonState::Processing, false, true),
+            [cancelled(DaemonState::Processing), DaemonEvent::Processing]
+        );
+        assert_eq!(
+            cancel_events(DaemonState::Processing, false, false),
+            [cancelled(DaemonState::Processing), DaemonEvent::Ready]
+        );
+
+        // A job cancelled while recording leaves the recording state alone
+        assert_eq!(
+            cancel_events(DaemonState::Processing, true, false),
+            [cancelled(DaemonState::Processing)]
+        );
+    }
+
+    #[test]
+    fn profiles_only_rebuild_the_components_they_change() {
+        let json = r#"{
+            "profiles": [
+                {"name": "prompt", "prompt": "Shell commands."},
+                {"name": "paste", "paste_method": "type"},
+                {"name": "german", "language": "de"}
+            ]
+        }"#;
+        let base: Config = serde_json::from_str(json).unwrap();
+        let with = |name| layered_config(&base, [None, Some(name), None]).unwrap();
+
+        let prompt = with("prompt");
+        assert!(!injector_needs_refresh(&base, &prompt));
+        assert!(!audio_feedback_needs_refresh(&base, &prompt));
+        assert!(injector_needs_refresh(&base, &with("paste")));
+        // The language picks the speech replacement pack
+        assert!(injector_needs_refresh(&base, &with("german")));
+    }
+
+    #[test]
+    fn layered_profiles_select_and_extend_speech_replacements() {
+        let json = r#"{
+            "speech_replacements": {"entries": [{"phrase": "arrow", "replacement": "->"}]},
+            "profiles": [
+                {"name": "german", "speech_packs": ["de"]},
+                {"name": "chat", "speech_replacements": [{"phrase": "arrow", "replacement": "→"}]}
+            ]
+        }"#;
+        let base: Config = serde_json::from_str(json).unwrap();
+
+        let config = layered_config(&base, [Some("german"), None, Some("chat")]).unwrap();
+        assert_eq!(
+            config.speech_replacements.packs,
+            Some(vec!["de".to_string()])
+        );
+        assert_eq!(config.speech_replacements.entries.len(), 2);
+
+        let packs_dir = std::path::Path::new("/nonexistent");
+        let (text, _) = SpeechReplacements::load(&config, packs_dir)
+            .unwrap()
+            .apply("a arrow b Punkt");
+        assert_eq!(
+            text.split_whitespace().collect::<Vec<_>>(),
+            ["a", "→", "b."]
+        );
+
+        // Without the profiles the base entries and the language's pack apply
+        let (text, _) = SpeechReplacements::load(&base, packs_dir)
+            .unwrap()
+            .apply("a arrow b Punkt");
+        assert_eq!(
+            text.split_whitespace().collect::<Vec<_>>(),
+            ["a", "->", "b", "Punkt"]
+        );
+    }
+}
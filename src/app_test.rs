@@ -7,9 +7,11 @@ use crate::audio::{
     capture::RecordingSession, AudioCapture, AudioFeedback, CapturedAudio, FastVad, FastVadOutcome,
 };
 use crate::config::{Config, ConfigManager, TranscriptionProvider};
+use crate::input::replacements::SpeechReplacements;
 use crate::input::TextInjector;
+use crate::llm::LlmPostProcessor;
 use crate::status::StatusWriter;
-use crate::transcription::{TranscriptionBackend, TranscriptionResult};
+use crate::transcription::{TranscriptionChain, TranscriptionResult, TranscriptionTask};
 use crate::whisper::WhisperVadOptions;
 
 /// Test version of the app that doesn't use global shortcuts
@@ -17,7 +19,8 @@ pub struct HyprwhsprAppTest {
     config_manager: ConfigManager,
     audio_capture: AudioCapture,
     audio_feedback: AudioFeedback,
-    transcriber: TranscriptionBackend,
+    transcriber: TranscriptionChain,
+    postprocessor: Option<LlmPostProcessor>,
     fast_vad: Option<FastVad>,
     text_injector: Arc<Mutex<TextInjector>>,
     status_writer: StatusWriter,
@@ -45,7 +48,7 @@ impl HyprwhsprAppTest {
 
         let vad_options = build_vad_options(&config_manager, &config);
 
-        let transcriber = TranscriptionBackend::build(&config_manager, &config, vad_options)
+        let mut transcriber = TranscriptionChain::build(&config_manager, &config, vad_options)
             .context("Failed to configure transcription backend")?;
 
         transcriber
@@ -57,13 +60,14 @@ impl HyprwhsprAppTest {
             transcriber.provider().label()
         );
 
-        let text_injector = TextInjector::new(
-            config.shift_paste,
-            config.global_paste_shortcut,
-            config.paste_hints.shift.clone(),
-            config.word_overrides.clone(),
-            config.auto_copy_clipboard,
-        )?;
+        let postprocessor = LlmPostProcessor::new(&config.llm_postprocess)
+            .context("Failed to configure LLM post-processing")?;
+
+        let speech_replacements =
+            SpeechReplacements::load(&config, &config_manager.get_speech_packs_dir())
+                .context("Failed to load speech replacements")?;
+
+        let text_injector = TextInjector::new(&config, speech_replacements)?;
 
         let status_writer = StatusWriter::new()?;
         status_writer.set_recording(false)?;
@@ -86,6 +90,7 @@ impl HyprwhsprAppTest {
             audio_capture,
             audio_feedback,
             transcriber,
+            postprocessor,
             fast_vad,
             text_injector: Arc::new(Mutex::new(text_injector)),
             status_writer,
@@ -117,21 +122,24 @@ impl HyprwhsprAppTest {
             new_config.stop_sound_volume,
         );
 
-        let text_injector = TextInjector::new(
-            new_config.shift_paste,
-            new_config.global_paste_shortcut,
-            new_config.paste_hints.shift.clone(),
-            new_config.word_overrides.clone(),
-            new_config.auto_copy_clipboard,
-        )?;
+        let speech_replacements =
+            SpeechReplacements::load(&new_config, &self.config_manager.get_speech_packs_dir())
+                .context("Failed to load speech replacements")?;
+
+        let text_injector = TextInjector::new(&new_config, speech_replacements)?;
 
         let transcriber_changed =
-            TranscriptionBackend::needs_refresh(&self.current_config, &new_config);
+            TranscriptionChain::needs_refresh(&self.current_config, &new_config);
+
+        if new_config.llm_postprocess != self.current_config.llm_postprocess {
+            self.postprocessor = LlmPostProcessor::new(&new_config.llm_postprocess)
+                .context("Failed to configure LLM post-processing")?;
+        }
 
         if transcriber_changed {
             let vad_options = build_vad_options(&self.config_manager, &new_config);
-            let backend =
-                TranscriptionBackend::build(&self.config_manager, &new_config, vad_options)
+            let mut backend =
+                TranscriptionChain::build(&self.config_manager, &new_config, vad_options)
                     .context("Failed to reconfigure transcription backend")?;
             backend
                 .initialize()
@@ -324,10 +332,21 @@ impl HyprwhsprAppTest {
             resample_audio(&samples, sample_rate, 16_000)
         };
 
-        let TranscriptionResult {
-            text: transcription,
-            ..
-        } = self.transcriber.transcribe(audio_for_transcription).await?;
+        let task = if self.current_config.transcription.translate {
+            TranscriptionTask::Translate
+        } else {
+            TranscriptionTask::Transcribe
+        };
+        let (
+            _,
+            TranscriptionResult {
+                text: transcription,
+                ..
+            },
+        ) = self
+            .transcriber
+            .transcribe(audio_for_transcription, task)
+            .await?;
 
         if transcription.trim().is_empty() {
             warn!("Empty transcription - Whisper couldn't understand the audio");
@@ -336,6 +355,11 @@ impl HyprwhsprAppTest {
 
         info!("📝 Transcription: \"{}\"", transcription);
 
+        let transcription = match &self.postprocessor {
+            Some(postprocessor) => postprocessor.process(&transcription).await,
+            None => transcription,
+        };
+
         let text_injector = Arc::clone(&self.text_injector);
         let mut injector = text_injector.lock().await;
 
@@ -31,6 +31,13 @@ impl CapturedAudio {
     pub fn len(&self) -> usize {
         self.samples.len()
     }
+
+    pub fn duration(&self) -> Duration {
+        if self.sample_rate == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(self.samples.len() as f64 / self.sample_rate as f64)
+    }
 }
 
 #[derive(Debug)]
@@ -237,6 +244,22 @@ impl RecordingSession {
         })
     }
 
+    /// Sample rate as measured so far, falling back to the requested rate
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate_tracker
+            .lock()
+            .map(|tracker| tracker.sample_rate())
+            .unwrap_or(self.requested_sample_rate)
+    }
+
+    /// Copy of everything captured past `offset`, for watching a recording live
+    pub fn samples_since(&self, offset: usize) -> Vec<f32> {
+        match self.audio_data.lock() {
+            Ok(data) if offset < data.len() => data[offset..].to_vec(),
+            _ => Vec::new(),
+        }
+    }
+
     pub fn get_current_level(&self) -> f32 {
         if let Ok(data) = self.audio_data.lock() {
             if data.is_empty() {
@@ -100,6 +100,16 @@ impl AudioFeedback {
         self.play_sound(&self.stop_sound, self.stop_volume)
     }
 
+    /// Heads-up that a recording is about to hit its length limit (reuses the stop sound)
+    pub fn play_warning_sound(&self) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        debug!("Playing warning sound: {:?}", self.stop_sound);
+        self.play_sound(&self.stop_sound, self.stop_volume)
+    }
+
     fn play_sound(&self, path: &PathBuf, volume: f32) -> Result<()> {
         if !path.exists() {
             warn!("Sound file not found: {:?}", path);
@@ -4,4 +4,4 @@ pub mod vad;
 
 pub use capture::{AudioCapture, CapturedAudio};
 pub use feedback::AudioFeedback;
-pub use vad::{FastVad, FastVadOutcome, FastVadProfile, FastVadSettings};
+pub use vad::{EndOfSpeechDetector, FastVad, FastVadOutcome, FastVadProfile, FastVadSettings};
@@ -452,6 +452,108 @@ impl FastVad {
     }
 }
 
+/// Streaming end-of-utterance detection for live capture. Feed it audio as it
+/// arrives; it reports once speech has been followed by enough trailing silence.
+pub struct EndOfSpeechDetector {
+    detector: VoiceActivityDetector,
+    sample_rate_hz: u32,
+    frame_samples: usize,
+    pending: Vec<f32>,
+    min_speech_frames: usize,
+    silence_timeout_frames: usize,
+    speech_run: usize,
+    silence_run: usize,
+    heard_speech: bool,
+}
+
+impl EndOfSpeechDetector {
+    pub fn new(config: &FastVadConfig, sample_rate_hz: u32) -> Result<Self> {
+        let silence_ms = config
+            .auto_stop_silence_ms
+            .unwrap_or(config.silence_timeout_ms);
+        Self::with_silence_ms(config, sample_rate_hz, silence_ms)
+    }
+
+    /// Like [`EndOfSpeechDetector::new`], but ending speech after `silence_ms` of silence.
+    pub fn with_silence_ms(
+        config: &FastVadConfig,
+        sample_rate_hz: u32,
+        silence_ms: u32,
+    ) -> Result<Self> {
+        FastVad::validate_sample_rate(sample_rate_hz)?;
+
+        Ok(Self {
+            detector: VoiceActivityDetector::new(FastVadProfile::from(config.profile).into()),
+            sample_rate_hz,
+            frame_samples: FastVad::frame_samples(sample_rate_hz),
+            pending: Vec::new(),
+            min_speech_frames: config.min_speech_ms.div_ceil(FRAME_MS).max(1) as usize,
+            silence_timeout_frames: silence_ms.div_ceil(FRAME_MS).max(1) as usize,
+            speech_run: 0,
+            silence_run: 0,
+            heard_speech: false,
+        })
+    }
+
+    pub fn sample_rate_hz(&self) -> u32 {
+        self.sample_rate_hz
+    }
+
+    pub fn heard_speech(&self) -> bool {
+        self.heard_speech
+    }
+
+    /// Forget the last utterance and wait for speech again.
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.speech_run = 0;
+        self.silence_run = 0;
+        self.heard_speech = false;
+    }
+
+    /// Consume newly captured samples. Returns true once the utterance has ended.
+    pub fn push(&mut self, samples: &[f32]) -> Result<bool> {
+        self.pending.extend_from_slice(samples);
+
+        let mut consumed = 0;
+        let mut ended = false;
+        while self.pending.len() - consumed >= self.frame_samples {
+            let frame = &self.pending[consumed..consumed + self.frame_samples];
+            let pcm_frame = FastVad::convert_frame(frame, self.frame_samples);
+            consumed += self.frame_samples;
+
+            if self.predict_frame(&pcm_frame)? {
+                self.speech_run += 1;
+                self.silence_run = 0;
+                if self.speech_run >= self.min_speech_frames {
+                    self.heard_speech = true;
+                }
+            } else {
+                self.speech_run = 0;
+                self.silence_run += 1;
+                if self.heard_speech && self.silence_run >= self.silence_timeout_frames {
+                    ended = true;
+                    break;
+                }
+            }
+        }
+
+        self.pending.drain(..consumed);
+        Ok(ended)
+    }
+
+    fn predict_frame(&mut self, frame: &[i16]) -> Result<bool> {
+        match self.sample_rate_hz {
+            8_000 => self.detector.predict_8khz(frame),
+            16_000 => self.detector.predict_16khz(frame),
+            32_000 => self.detector.predict_32khz(frame),
+            48_000 => self.detector.predict_48khz(frame),
+            rate => bail!("Unsupported sample rate {} Hz for fast VAD", rate),
+        }
+        .context("Earshot VAD failed to evaluate live frame")
+    }
+}
+
 impl fmt::Debug for FastVad {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("FastVad")
@@ -17,6 +17,7 @@ pub struct BenchmarkRecorder {
     keybind_stop: Option<Instant>,
     recording_start: Instant,
     recording_stop: Option<Instant>,
+    forced_stop: bool,
     processing_start: Option<Instant>,
     original_samples: Option<usize>,
     original_sample_rate: Option<u32>,
@@ -45,6 +46,7 @@ impl BenchmarkRecorder {
             keybind_stop: None,
             recording_start,
             recording_stop: None,
+            forced_stop: false,
             processing_start: None,
             original_samples: None,
             original_sample_rate: None,
@@ -74,6 +76,11 @@ impl BenchmarkRecorder {
         self.recording_stop = Some(at);
     }
 
+    /// The recording hit its length limit instead of being stopped by the user
+    pub fn mark_forced_stop(&mut self) {
+        self.forced_stop = true;
+    }
+
     pub fn record_original_audio(&mut self, samples: usize, sample_rate: u32) {
         if sample_rate > 0 {
             self.original_samples = Some(samples);
@@ -120,6 +127,14 @@ impl BenchmarkRecorder {
         self.transcription_duration = Some(metrics.transcription_duration);
     }
 
+    /// Note the backend that produced the text when a fallback stepped in
+    pub fn record_provider(&mut self, provider_label: &str) {
+        if provider_label != self.provider_label {
+            self.provider_label =
+                format!("{} (fallback from {})", provider_label, self.provider_label);
+        }
+    }
+
     pub fn mark_injection_start(&mut self, at: Instant) {
         self.injection_start = Some(at);
     }
@@ -204,6 +219,7 @@ impl BenchmarkRecorder {
             provider_label: self.provider_label,
             keybind_to_record_start_ms,
             recording_duration_ms,
+            forced_stop: self.forced_stop,
             stop_to_processing_ms,
             fast_vad_trim_ms,
             encode_ms,
@@ -270,6 +286,7 @@ pub(crate) struct BenchmarkSummary {
     provider_label: String,
     keybind_to_record_start_ms: f64,
     recording_duration_ms: Option<f64>,
+    forced_stop: bool,
     stop_to_processing_ms: Option<f64>,
     fast_vad_trim_ms: Option<f64>,
     encode_ms: Option<f64>,
@@ -320,8 +337,13 @@ impl fmt::Display for BenchmarkSummary {
             empty_cell(),
         ]));
 
+        let recording_label = if self.forced_stop {
+            "Recording (limit hit)"
+        } else {
+            "Recording (active)"
+        };
         table.add_row(Row::from(vec![
-            Cell::new("Recording (active)"),
+            Cell::new(recording_label),
             ms_cell(self.recording_duration_ms),
             ms_cell(self.original_audio_ms),
             kb_cell(self.original_audio_kb),
@@ -15,6 +15,9 @@ pub struct Cli {
 pub enum Command {
     /// Install integration components (waybar, systemd, elephant)
     Install(InstallArgs),
+
+    /// Control the running daemon over its socket
+    Ctl(CtlArgs),
 }
 
 #[derive(clap::Args)]
@@ -46,3 +49,54 @@ impl InstallArgs {
         self.waybar || self.service || self.elephant || self.all
     }
 }
+
+#[derive(clap::Args)]
+pub struct CtlArgs {
+    #[command(subcommand)]
+    pub action: CtlAction,
+}
+
+#[derive(Subcommand)]
+pub enum CtlAction {
+    /// Start a recording
+    Start,
+
+    /// Stop the active recording and transcribe it
+    Stop,
+
+    /// Start a recording, or stop the active one
+    Toggle,
+
+    /// Discard the active recording without transcribing it
+    Cancel,
+
+    /// Print the daemon state as JSON
+    Status,
+
+    /// Stream daemon events as newline-delimited JSON until interrupted
+    Subscribe,
+
+    /// Switch profiles: a profile name, `next`, `previous` or `none`
+    Profile { target: String },
+}
+
+/// What `ctl profile` switches to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileTarget {
+    Next,
+    Previous,
+    /// The base config, without a profile
+    None,
+    Named(String),
+}
+
+impl ProfileTarget {
+    pub fn parse(target: &str) -> Self {
+        match target.trim() {
+            "next" => Self::Next,
+            "previous" | "prev" => Self::Previous,
+            "none" | "" => Self::None,
+            name => Self::Named(name.to_string()),
+        }
+    }
+}
@@ -3,7 +3,7 @@ use crate::transcription::DEFAULT_PROMPT;
 use anyhow::{anyhow, Context, Result};
 use jsonc_parser::{parse_to_serde_value, ParseOptions};
 use serde::{Deserialize, Deserializer, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -21,6 +21,18 @@ pub struct ShortcutsConfig {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub press: Option<String>,
+
+    /// Discards the active recording or in-flight transcription
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancel: Option<String>,
+
+    /// Toggles a recording that is translated to English instead of transcribed verbatim
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub translate: Option<String>,
+
+    /// Further shortcuts, each with its own action and optional profile
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub bindings: Vec<ShortcutBinding>,
 }
 
 impl Default for ShortcutsConfig {
@@ -28,20 +40,232 @@ impl Default for ShortcutsConfig {
         Self {
             hold: None,
             press: Some(default_primary_shortcut()),
+            cancel: None,
+            translate: None,
+            bindings: Vec::new(),
         }
     }
 }
 
+impl ShortcutsConfig {
+    /// Every shortcut to listen for: the `press`, `hold`, `translate` and `cancel` slots
+    /// followed by `bindings`
+    pub fn all_bindings(&self) -> Vec<ShortcutBinding> {
+        let slots = [
+            (&self.press, ShortcutAction::Dictate, false),
+            (&self.hold, ShortcutAction::Dictate, true),
+            (&self.translate, ShortcutAction::Translate, false),
+            (&self.cancel, ShortcutAction::Cancel, false),
+        ];
+
+        slots
+            .into_iter()
+            .filter_map(|(keys, action, hold)| {
+                keys.clone().map(|keys| ShortcutBinding {
+                    keys,
+                    action,
+                    hold,
+                    profile: None,
+                })
+            })
+            .chain(self.bindings.iter().cloned())
+            .collect()
+    }
+}
+
+/// What a shortcut binding does when triggered
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutAction {
+    /// Record, then paste the transcription
+    #[default]
+    Dictate,
+    /// Record, then paste the English translation
+    Translate,
+    /// Record, then run the voice commands that were spoken and paste nothing
+    Command,
+    /// Discard the active recording or in-flight transcription
+    Cancel,
+    /// Paste the last transcription again
+    Reinject,
+}
+
+impl ShortcutAction {
+    pub fn records(self) -> bool {
+        matches!(self, Self::Dictate | Self::Translate | Self::Command)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ShortcutBinding {
+    /// Key combination, e.g. `SUPER+ALT+G`
+    pub keys: String,
+
+    #[serde(default)]
+    pub action: ShortcutAction,
+
+    /// Record only while the keys are held instead of toggling on each press
+    #[serde(default)]
+    pub hold: bool,
+
+    /// Profile applied to recordings started from this shortcut
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 pub struct PasteHintsConfig {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub shift: Vec<String>,
+
+    /// Window classes that get the text typed instead of pasted, as with `paste_method: "type"`
+    #[serde(default, rename = "type", skip_serializing_if = "Vec::is_empty")]
+    pub type_text: Vec<String>,
 }
 
 impl Default for PasteHintsConfig {
     fn default() -> Self {
-        Self { shift: Vec::new() }
+        Self {
+            shift: Vec::new(),
+            type_text: Vec::new(),
+        }
+    }
+}
+
+/// How injected text is pasted into the focused window
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PasteMethod {
+    /// Shift+Insert with `global_paste_shortcut`, else Ctrl+V or Ctrl+Shift+V by window class
+    #[default]
+    Auto,
+    CtrlV,
+    CtrlShiftV,
+    ShiftInsert,
+    /// Type the text as key events, leaving the clipboard untouched
+    Type,
+}
+
+/// Settings that take over while a profile is active. Unset fields keep the base config.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ProfileConfig {
+    pub name: String,
+    /// Select the profile when the focused window matches (Hyprland only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window: Option<WindowMatch>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<TranscriptionProvider>,
+    /// Transcription prompt for every provider
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Added to `word_overrides`, replacing entries with the same phrase
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub word_overrides: HashMap<String, String>,
+    /// Applied after the base `word_override_rules`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub word_override_rules: Vec<WordOverrideRule>,
+    /// Replaces `speech_replacements.packs`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speech_packs: Option<Vec<String>>,
+    /// Added after `speech_replacements.entries`, replacing entries with the same phrase
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub speech_replacements: Vec<SpeechReplacementEntry>,
+    /// Turn `llm_postprocess` on or off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub llm_postprocess: Option<bool>,
+    /// Entry of `llm_postprocess.profiles` to use; turns post-processing on unless
+    /// `llm_postprocess` is `false`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub llm_profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trailing_whitespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paste_method: Option<PasteMethod>,
+}
+
+/// Focused window a profile applies to. Every condition given has to match.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct WindowMatch {
+    /// Window classes, compared case-insensitively
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub class: Vec<String>,
+    /// Regular expression searched in the window title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<TitlePattern>,
+}
+
+impl WindowMatch {
+    pub fn matches(&self, class: Option<&str>, title: Option<&str>) -> bool {
+        if self.class.is_empty() && self.title.is_none() {
+            return false;
+        }
+
+        let class_matches = self.class.is_empty()
+            || class.is_some_and(|class| {
+                self.class
+                    .iter()
+                    .any(|candidate| candidate.trim().eq_ignore_ascii_case(class))
+            });
+
+        let title_matches = match &self.title {
+            None => true,
+            Some(pattern) => title.is_some_and(|title| pattern.is_match(title)),
+        };
+
+        class_matches && title_matches
+    }
+}
+
+/// Window title regular expression, compiled when the config is read. An invalid pattern
+/// matches no title.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub struct TitlePattern {
+    pattern: String,
+    regex: Result<regex::Regex, regex::Error>,
+}
+
+impl TitlePattern {
+    pub fn new(pattern: impl Into<String>) -> Self {
+        let pattern = pattern.into();
+        let regex = regex::Regex::new(&pattern);
+        Self { pattern, regex }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Why the pattern failed to compile
+    pub fn error(&self) -> Option<&regex::Error> {
+        self.regex.as_ref().err()
+    }
+
+    pub fn is_match(&self, title: &str) -> bool {
+        self.regex.as_ref().is_ok_and(|regex| regex.is_match(title))
+    }
+}
+
+impl PartialEq for TitlePattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern
+    }
+}
+
+impl From<String> for TitlePattern {
+    fn from(pattern: String) -> Self {
+        Self::new(pattern)
+    }
+}
+
+impl From<TitlePattern> for String {
+    fn from(pattern: TitlePattern) -> Self {
+        pattern.pattern
     }
 }
 
@@ -56,6 +280,15 @@ pub struct Config {
     #[serde(default)]
     pub word_overrides: HashMap<String, String>,
 
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub word_override_rules: Vec<WordOverrideRule>,
+
+    #[serde(default)]
+    pub speech_replacements: SpeechReplacementsConfig,
+
+    #[serde(default)]
+    pub voice_commands: VoiceCommandsConfig,
+
     #[serde(default)]
     pub audio_feedback: bool,
 
@@ -83,15 +316,48 @@ pub struct Config {
     #[serde(default)]
     pub paste_hints: PasteHintsConfig,
 
+    #[serde(default)]
+    pub paste_method: PasteMethod,
+
+    /// Pause between characters when typing instead of pasting (0 types as fast as possible)
+    #[serde(default)]
+    pub type_delay_ms: u32,
+
+    /// Appended after every injection, e.g. `" "` to keep dictations apart
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub trailing_whitespace: String,
+
+    /// Named sets of overrides, selected with `active_profile` or by the focused window
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<ProfileConfig>,
+
+    /// Profile applied at startup; switch at runtime with `hyprwhspr-rs ctl profile`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+
     #[serde(default)]
     pub audio_device: Option<usize>,
 
+    /// Recordings are stopped after this many seconds (0 = unlimited)
+    #[serde(default = "default_max_recording_secs")]
+    pub max_recording_secs: u32,
+
+    /// Seconds before `max_recording_secs` to warn that the recording will stop (0 = no warning)
+    #[serde(default = "default_max_recording_warning_secs")]
+    pub max_recording_warning_secs: u32,
+
     #[serde(default)]
     pub fast_vad: FastVadConfig,
 
+    #[serde(default)]
+    pub streaming: StreamingConfig,
+
     #[serde(default)]
     pub transcription: TranscriptionConfig,
 
+    #[serde(default)]
+    pub llm_postprocess: LlmPostProcessConfig,
+
     #[serde(default, rename = "model", skip_serializing)]
     legacy_model: Option<String>,
 
@@ -149,6 +415,14 @@ fn default_shift_paste() -> bool {
     true
 }
 
+fn default_max_recording_secs() -> u32 {
+    300
+}
+
+fn default_max_recording_warning_secs() -> u32 {
+    10
+}
+
 fn default_no_speech_threshold() -> f32 {
     0.60
 }
@@ -193,6 +467,78 @@ fn default_vad_samples_overlap() -> f32 {
     0.10
 }
 
+fn default_transcription_language() -> String {
+    "en".to_string()
+}
+
+fn default_voice_commands() -> BTreeMap<String, String> {
+    [
+        ("press enter", "Return"),
+        ("press return", "Return"),
+        ("press tab", "Tab"),
+        ("press backspace", "BackSpace"),
+        ("press delete", "Delete"),
+        ("press escape", "Escape"),
+        ("arrow up", "Up"),
+        ("arrow down", "Down"),
+        ("arrow left", "Left"),
+        ("arrow right", "Right"),
+        ("select all", "CTRL+A"),
+        ("undo", "CTRL+Z"),
+        ("undo that", "CTRL+Z"),
+        ("redo", "CTRL+SHIFT+Z"),
+        ("scratch that", "delete_last"),
+        ("delete that", "delete_last"),
+    ]
+    .into_iter()
+    .map(|(phrase, action)| (phrase.to_string(), action.to_string()))
+    .collect()
+}
+
+fn default_llm_base_url() -> String {
+    "http://127.0.0.1:8080/v1".to_string()
+}
+
+fn default_llm_model() -> String {
+    "local".to_string()
+}
+
+fn default_llm_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_llm_prompt() -> String {
+    "You clean up dictated text. Fix grammar, punctuation and capitalization and remove filler words such as \"um\" and \"uh\". Keep the meaning and wording otherwise unchanged. Reply with the corrected text only.".to_string()
+}
+
+fn default_hallucination_phrases() -> Vec<String> {
+    [
+        "Thanks for watching",
+        "Thank you for watching",
+        "Thanks for listening",
+        "Please subscribe",
+        "Like and subscribe",
+        "Don't forget to subscribe",
+        // Credits only as the full strings models reproduce; "Transcribed by" alone is
+        // ordinary speech
+        "Subtitles by the Amara.org community",
+        "Subtitles made by the community of Amara.org",
+        "Transcription by CastingWords",
+        "Transcribed by https://otter.ai",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+fn default_hallucination_max_repeats() -> usize {
+    4
+}
+
+fn default_hallucination_max_chars_per_second() -> f32 {
+    40.0
+}
+
 fn default_transcription_request_timeout_secs() -> u64 {
     45
 }
@@ -201,6 +547,18 @@ fn default_transcription_max_retries() -> u32 {
     2
 }
 
+fn default_whisper_server_host() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_whisper_server_port() -> u16 {
+    8178
+}
+
+fn default_whisper_server_startup_timeout_secs() -> u64 {
+    60
+}
+
 fn default_groq_model() -> String {
     "whisper-large-v3-turbo".to_string()
 }
@@ -209,6 +567,19 @@ fn default_groq_endpoint() -> String {
     "https://api.groq.com/openai/v1/audio/transcriptions".to_string()
 }
 
+fn default_groq_translation_model() -> String {
+    // Groq only serves translations with the full large-v3 model
+    "whisper-large-v3".to_string()
+}
+
+fn default_openai_compatible_base_url() -> String {
+    "http://localhost:8000/v1".to_string()
+}
+
+fn default_openai_compatible_model() -> String {
+    "Systran/faster-whisper-small".to_string()
+}
+
 fn default_gemini_model() -> String {
     "gemini-2.5-pro-exp-0827".to_string()
 }
@@ -225,6 +596,14 @@ fn default_gemini_max_output_tokens() -> u32 {
     1024
 }
 
+fn default_streaming_pause_ms() -> u32 {
+    700
+}
+
+fn default_streaming_min_chunk_ms() -> u32 {
+    2000
+}
+
 fn default_fast_vad_min_speech_ms() -> u32 {
     120
 }
@@ -313,6 +692,156 @@ pub struct FastVadConfig {
     pub volatility_window: u32,
     pub volatility_increase_threshold: f32,
     pub volatility_decrease_threshold: f32,
+    /// Stop press-triggered recordings once speech is followed by trailing silence
+    pub auto_stop: bool,
+    /// Trailing silence that ends the recording; falls back to `silence_timeout_ms`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_stop_silence_ms: Option<u32>,
+}
+
+/// An ordered override, applied after the plain `word_overrides` map
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WordOverrideRule {
+    /// Whole word or phrase, matched case-insensitively, unless `regex` is set
+    pub pattern: String,
+    /// With `regex`, may reference capture groups as `$1` or `${name}`
+    pub replacement: String,
+    /// Treat `pattern` as a regular expression, used as written (add `(?i)` to ignore case)
+    #[serde(default)]
+    pub regex: bool,
+    /// Follow the matched text's casing: "Github" → "GitHub", "GITHUB" → "GITHUB"
+    #[serde(default)]
+    pub preserve_case: bool,
+    /// Only apply when the focused window has one of these classes (Hyprland only);
+    /// empty applies everywhere
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub window_classes: Vec<String>,
+}
+
+/// Spoken punctuation and symbols ("comma", "open paren") replaced while injecting
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SpeechReplacementsConfig {
+    /// Packs to load in order: built-in (`en`, `de`) or `<name>.jsonc` in the `speech_packs`
+    /// directory next to this config, which takes precedence. Unset picks the pack for
+    /// `transcription.language`, falling back to `en`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub packs: Option<Vec<String>>,
+    /// Applied on top of the packs; an entry with the same phrase replaces the pack's
+    pub entries: Vec<SpeechReplacementEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SpeechReplacementEntry {
+    pub phrase: String,
+    pub replacement: String,
+    /// Replace punctuation right before the phrase, so "done, period" becomes "done."
+    #[serde(default)]
+    pub adjust_preceding_punct: bool,
+}
+
+/// Spoken commands that act on the focused application instead of being typed. A command
+/// is only recognised when it is a sentence of its own, e.g. "Fix the typo. Press enter."
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct VoiceCommandsConfig {
+    /// Recognise commands in dictation. Off by default, since a dictated "Undo." would
+    /// otherwise press keys; shortcuts with the `command` action use them either way.
+    pub enabled: bool,
+    /// Spoken phrase → key chord (`Return`, `CTRL+Z`, ...) or `delete_last`, which erases
+    /// the previous dictation. Setting this replaces the built-in vocabulary.
+    pub commands: BTreeMap<String, String>,
+    /// Spoken identifier casing: "format snake case user id" pastes `user_id`
+    pub case_formatting: bool,
+}
+
+impl Default for VoiceCommandsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            commands: default_voice_commands(),
+            case_formatting: false,
+        }
+    }
+}
+
+/// Transcribe and inject speech in chunks while the recording is still running
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct StreamingConfig {
+    pub enabled: bool,
+    /// Pause in speech that closes a chunk
+    pub pause_ms: u32,
+    /// Chunks shorter than this keep growing until the next pause
+    pub min_chunk_ms: u32,
+}
+
+impl Default for StreamingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            pause_ms: default_streaming_pause_ms(),
+            min_chunk_ms: default_streaming_min_chunk_ms(),
+        }
+    }
+}
+
+/// Rewrite transcripts with an OpenAI-compatible chat-completions endpoint before injection
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct LlmPostProcessConfig {
+    pub enabled: bool,
+    /// API root; `/chat/completions` is appended
+    pub base_url: String,
+    pub model: String,
+    /// Environment variable holding the API key (no auth header when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+    /// File containing the API key, used when `api_key_env` is unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key_file: Option<String>,
+    /// The unmodified transcript is injected when the model has not answered by then
+    pub timeout_ms: u64,
+    pub temperature: f32,
+    /// System prompt used when no profile is selected
+    pub prompt: String,
+    /// Named system prompts, e.g. `"commit": "Rewrite as a git commit message"`
+    pub profiles: BTreeMap<String, String>,
+    /// Entry of `profiles` to use instead of `prompt`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+impl Default for LlmPostProcessConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            base_url: default_llm_base_url(),
+            model: default_llm_model(),
+            api_key_env: None,
+            api_key_file: None,
+            timeout_ms: default_llm_timeout_ms(),
+            temperature: 0.0,
+            prompt: default_llm_prompt(),
+            profiles: BTreeMap::new(),
+            profile: None,
+        }
+    }
+}
+
+impl LlmPostProcessConfig {
+    /// The selected profile's prompt, or `prompt` when none is selected. A profile missing
+    /// from `profiles` is an error so a typo does not silently fall back.
+    pub fn system_prompt(&self) -> Result<&str> {
+        match self.profile.as_deref().map(str::trim) {
+            None | Some("") => Ok(&self.prompt),
+            Some(name) => self
+                .profiles
+                .get(name)
+                .map(String::as_str)
+                .ok_or_else(|| anyhow!("Unknown llm_postprocess profile: {}", name)),
+        }
+    }
 }
 
 impl Default for FastVadConfig {
@@ -327,6 +856,8 @@ impl Default for FastVadConfig {
             volatility_window: default_fast_vad_volatility_window(),
             volatility_increase_threshold: default_fast_vad_volatility_increase_threshold(),
             volatility_decrease_threshold: default_fast_vad_volatility_decrease_threshold(),
+            auto_stop: false,
+            auto_stop_silence_ms: None,
         }
     }
 }
@@ -338,6 +869,8 @@ pub enum TranscriptionProvider {
     Groq,
     Gemini,
     Parakeet,
+    #[serde(rename = "openai_compatible")]
+    OpenAiCompatible,
 }
 
 impl Default for TranscriptionProvider {
@@ -353,6 +886,7 @@ impl TranscriptionProvider {
             TranscriptionProvider::Groq => "Groq",
             TranscriptionProvider::Gemini => "Gemini",
             TranscriptionProvider::Parakeet => "Parakeet TDT",
+            TranscriptionProvider::OpenAiCompatible => "OpenAI-compatible",
         }
     }
 }
@@ -368,6 +902,7 @@ pub struct WhisperCppConfig {
     pub no_speech_threshold: f32,
     pub models_dirs: Vec<String>,
     pub vad: VadConfig,
+    pub server: WhisperServerConfig,
 }
 
 impl Default for WhisperCppConfig {
@@ -381,6 +916,37 @@ impl Default for WhisperCppConfig {
             no_speech_threshold: default_no_speech_threshold(),
             models_dirs: Vec::new(),
             vad: VadConfig::default(),
+            server: WhisperServerConfig::default(),
+        }
+    }
+}
+
+/// Keeps the model warm in a long-lived `whisper-server` instead of running whisper-cli per utterance
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct WhisperServerConfig {
+    pub enabled: bool,
+    /// Attach to an already running server instead of launching one (e.g. `http://127.0.0.1:8080`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// whisper-server binary to launch; searched next to whisper-cli when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binary: Option<String>,
+    pub host: String,
+    pub port: u16,
+    /// How long to wait for the server to load the model before a transcription gives up
+    pub startup_timeout_secs: u64,
+}
+
+impl Default for WhisperServerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: None,
+            binary: None,
+            host: default_whisper_server_host(),
+            port: default_whisper_server_port(),
+            startup_timeout_secs: default_whisper_server_startup_timeout_secs(),
         }
     }
 }
@@ -389,6 +955,8 @@ impl Default for WhisperCppConfig {
 #[serde(default)]
 pub struct GroqConfig {
     pub model: String,
+    /// Model used for `/audio/translations` requests
+    pub translation_model: String,
     pub endpoint: String,
     pub prompt: String,
 }
@@ -397,12 +965,76 @@ impl Default for GroqConfig {
     fn default() -> Self {
         Self {
             model: default_groq_model(),
+            translation_model: default_groq_translation_model(),
             endpoint: default_groq_endpoint(),
             prompt: default_whisper_prompt(),
         }
     }
 }
 
+/// Container used when uploading audio to cloud and HTTP providers
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadFormat {
+    /// Lossless and roughly half the size of WAV
+    #[default]
+    Flac,
+    /// Uncompressed 16-bit PCM, for servers that cannot decode FLAC
+    Wav,
+}
+
+/// Any server implementing OpenAI's `/audio/transcriptions` (speaches, faster-whisper-server, ...)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct OpenAiCompatibleConfig {
+    /// API root; `/audio/transcriptions` is appended
+    pub base_url: String,
+    pub model: String,
+    /// Environment variable holding the API key (no auth header when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+    /// File containing the API key, used when `api_key_env` is unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key_file: Option<String>,
+    pub response_format: OpenAiResponseFormat,
+    /// Additional multipart form fields sent with every request (e.g. `language`, `temperature`)
+    pub extra_fields: BTreeMap<String, String>,
+    pub prompt: String,
+}
+
+impl Default for OpenAiCompatibleConfig {
+    fn default() -> Self {
+        Self {
+            base_url: default_openai_compatible_base_url(),
+            model: default_openai_compatible_model(),
+            api_key_env: None,
+            api_key_file: None,
+            response_format: OpenAiResponseFormat::default(),
+            extra_fields: BTreeMap::new(),
+            prompt: default_whisper_prompt(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OpenAiResponseFormat {
+    #[default]
+    Json,
+    VerboseJson,
+    Text,
+}
+
+impl OpenAiResponseFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OpenAiResponseFormat::Json => "json",
+            OpenAiResponseFormat::VerboseJson => "verbose_json",
+            OpenAiResponseFormat::Text => "text",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 pub struct GeminiConfig {
@@ -446,38 +1078,114 @@ impl Default for ParakeetConfig {
     }
 }
 
+/// Filters output that Whisper-style models invent on silence or near-silent audio
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct HallucinationGuardConfig {
+    pub enabled: bool,
+    /// Sentences starting with one of these are dropped (case and punctuation ignored)
+    pub phrases: Vec<String>,
+    /// A word or phrase repeated back to back more often than this is cut to one
+    /// occurrence; 0 turns the check off
+    pub max_repeats: usize,
+    /// Output longer than this many characters per second of audio is dropped; 0 turns
+    /// the check off
+    pub max_chars_per_second: f32,
+}
+
+impl Default for HallucinationGuardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            phrases: default_hallucination_phrases(),
+            max_repeats: default_hallucination_max_repeats(),
+            max_chars_per_second: default_hallucination_max_chars_per_second(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 pub struct TranscriptionConfig {
     pub provider: TranscriptionProvider,
+    /// Providers tried in order when the primary one fails or returns no usable text
+    pub fallback: Vec<TranscriptionProvider>,
+    /// Spoken language as an ISO 639-1 code (`en`, `de`, ...), or `auto` to detect it
+    pub language: String,
+    /// Translate every recording to English, not only those started with `shortcuts.translate`
+    pub translate: bool,
     pub request_timeout_secs: u64,
     pub max_retries: u32,
+    pub upload_format: UploadFormat,
+    pub hallucination_guard: HallucinationGuardConfig,
     pub whisper_cpp: WhisperCppConfig,
     pub groq: GroqConfig,
     pub gemini: GeminiConfig,
     pub parakeet: ParakeetConfig,
+    pub openai_compatible: OpenAiCompatibleConfig,
 }
 
 impl Default for TranscriptionConfig {
     fn default() -> Self {
         Self {
             provider: TranscriptionProvider::default(),
+            fallback: Vec::new(),
+            language: default_transcription_language(),
+            translate: false,
             request_timeout_secs: default_transcription_request_timeout_secs(),
             max_retries: default_transcription_max_retries(),
+            upload_format: UploadFormat::default(),
+            hallucination_guard: HallucinationGuardConfig::default(),
             whisper_cpp: WhisperCppConfig::default(),
             groq: GroqConfig::default(),
             gemini: GeminiConfig::default(),
             parakeet: ParakeetConfig::default(),
+            openai_compatible: OpenAiCompatibleConfig::default(),
         }
     }
 }
 
+impl TranscriptionConfig {
+    /// The configured language code, or `None` when it should be detected from the audio
+    pub fn language_code(&self) -> Option<String> {
+        let language = self.language.trim().to_lowercase();
+        if language.is_empty() || language == "auto" {
+            None
+        } else {
+            Some(language)
+        }
+    }
+
+    /// Use `prompt` for every provider
+    pub fn set_prompt(&mut self, prompt: String) {
+        self.whisper_cpp.prompt = prompt.clone();
+        self.groq.prompt = prompt.clone();
+        self.gemini.prompt = prompt.clone();
+        self.openai_compatible.prompt = prompt.clone();
+        self.parakeet.prompt = prompt;
+    }
+
+    /// The primary provider followed by its fallbacks, without duplicates
+    pub fn provider_chain(&self) -> Vec<TranscriptionProvider> {
+        let mut chain = vec![self.provider.clone()];
+        for provider in &self.fallback {
+            if !chain.contains(provider) {
+                chain.push(provider.clone());
+            }
+        }
+        chain
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         let mut config = Self {
             primary_shortcut: default_primary_shortcut(),
             shortcuts: ShortcutsConfig::default(),
             word_overrides: HashMap::new(),
+            word_override_rules: Vec::new(),
+            speech_replacements: SpeechReplacementsConfig::default(),
+            voice_commands: VoiceCommandsConfig::default(),
             audio_feedback: false,
             start_sound_volume: default_volume(),
             stop_sound_volume: default_volume(),
@@ -487,9 +1195,18 @@ impl Default for Config {
             shift_paste: default_shift_paste(),
             global_paste_shortcut: false,
             paste_hints: PasteHintsConfig::default(),
+            paste_method: PasteMethod::default(),
+            type_delay_ms: 0,
+            trailing_whitespace: String::new(),
+            profiles: Vec::new(),
+            active_profile: None,
             audio_device: None,
+            max_recording_secs: default_max_recording_secs(),
+            max_recording_warning_secs: default_max_recording_warning_secs(),
             fast_vad: FastVadConfig::default(),
+            streaming: StreamingConfig::default(),
             transcription: TranscriptionConfig::default(),
+            llm_postprocess: LlmPostProcessConfig::default(),
             legacy_model: None,
             legacy_threads: None,
             legacy_gpu_layers: None,
@@ -518,6 +1235,25 @@ impl Config {
             .hold
             .as_ref()
             .and_then(|value| Self::sanitize_shortcut(value));
+        self.shortcuts.cancel = self
+            .shortcuts
+            .cancel
+            .as_ref()
+            .and_then(|value| Self::sanitize_shortcut(value));
+        self.shortcuts.translate = self
+            .shortcuts
+            .translate
+            .as_ref()
+            .and_then(|value| Self::sanitize_shortcut(value));
+        self.shortcuts.bindings.retain_mut(|binding| {
+            match Self::sanitize_shortcut(&binding.keys) {
+                Some(keys) => {
+                    binding.keys = keys;
+                    true
+                }
+                None => false,
+            }
+        });
 
         if let (Some(current), Some(legacy)) = (&self.shortcuts.press, &legacy_primary) {
             if current != legacy {
@@ -552,10 +1288,7 @@ impl Config {
         }
 
         if let Some(prompt) = self.legacy_whisper_prompt.take() {
-            self.transcription.whisper_cpp.prompt = prompt.clone();
-            self.transcription.groq.prompt = prompt.clone();
-            self.transcription.gemini.prompt = prompt.clone();
-            self.transcription.parakeet.prompt = prompt;
+            self.transcription.set_prompt(prompt);
         }
 
         if let Some(dirs) = self.legacy_models_dirs.take() {
@@ -575,6 +1308,122 @@ impl Config {
         }
     }
 
+    /// Warn about profile settings that can never take effect
+    pub fn validate_profiles(&self) {
+        for profile in &self.profiles {
+            let title = profile
+                .window
+                .as_ref()
+                .and_then(|window| window.title.as_ref());
+            if let Some(err) = title.and_then(TitlePattern::error) {
+                tracing::warn!(
+                    "Profile {:?} has an invalid window title pattern and won't match any window: {}",
+                    profile.name,
+                    err
+                );
+            }
+        }
+
+        for binding in &self.shortcuts.bindings {
+            if let Some(name) = &binding.profile {
+                if self.profile(name).is_none() {
+                    tracing::warn!(
+                        "Shortcut {} refers to undefined profile {:?}",
+                        binding.keys,
+                        name
+                    );
+                }
+            }
+        }
+    }
+
+    pub fn profile(&self, name: &str) -> Option<&ProfileConfig> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    /// The first profile whose `window` matches the focused window
+    pub fn window_profile(
+        &self,
+        class: Option<&str>,
+        title: Option<&str>,
+    ) -> Option<&ProfileConfig> {
+        self.profiles.iter().find(|profile| {
+            profile
+                .window
+                .as_ref()
+                .is_some_and(|window| window.matches(class, title))
+        })
+    }
+
+    /// The profile after (or before, when `forward` is false) `current` in config order, with
+    /// no profile between the last and the first
+    pub fn cycle_profile(&self, current: Option<&str>, forward: bool) -> Option<&ProfileConfig> {
+        let index = current.and_then(|name| {
+            self.profiles
+                .iter()
+                .position(|profile| profile.name == name)
+        });
+        match (index, forward) {
+            (None, true) => self.profiles.first(),
+            (None, false) => self.profiles.last(),
+            (Some(index), true) => self.profiles.get(index + 1),
+            (Some(index), false) => index.checked_sub(1).and_then(|i| self.profiles.get(i)),
+        }
+    }
+
+    pub fn has_window_profiles(&self) -> bool {
+        self.profiles.iter().any(|profile| profile.window.is_some())
+    }
+
+    /// This config with the named profile's overrides applied
+    pub fn with_profile(&self, name: &str) -> Result<Config> {
+        let profile = self
+            .profile(name)
+            .ok_or_else(|| anyhow!("Unknown profile: {}", name))?;
+        let mut config = self.clone();
+
+        if let Some(provider) = &profile.provider {
+            config.transcription.provider = provider.clone();
+        }
+        if let Some(prompt) = &profile.prompt {
+            config.transcription.set_prompt(prompt.clone());
+        }
+        if let Some(language) = &profile.language {
+            config.transcription.language = language.clone();
+        }
+        config.word_overrides.extend(
+            profile
+                .word_overrides
+                .iter()
+                .map(|(phrase, replacement)| (phrase.clone(), replacement.clone())),
+        );
+        config
+            .word_override_rules
+            .extend(profile.word_override_rules.iter().cloned());
+        if let Some(packs) = &profile.speech_packs {
+            config.speech_replacements.packs = Some(packs.clone());
+        }
+        config
+            .speech_replacements
+            .entries
+            .extend(profile.speech_replacements.iter().cloned());
+        if let Some(llm_profile) = &profile.llm_profile {
+            config.llm_postprocess.profile = Some(llm_profile.clone());
+            config.llm_postprocess.enabled = true;
+        }
+        if let Some(enabled) = profile.llm_postprocess {
+            config.llm_postprocess.enabled = enabled;
+        }
+        if let Some(trailing) = &profile.trailing_whitespace {
+            config.trailing_whitespace = trailing.clone();
+        }
+        if let Some(method) = profile.paste_method {
+            config.paste_method = method;
+        }
+
+        Ok(config)
+    }
+
     pub fn press_shortcut(&self) -> Option<&str> {
         self.shortcuts.press.as_deref()
     }
@@ -583,6 +1432,14 @@ impl Config {
         self.shortcuts.hold.as_deref()
     }
 
+    pub fn cancel_shortcut(&self) -> Option<&str> {
+        self.shortcuts.cancel.as_deref()
+    }
+
+    pub fn translate_shortcut(&self) -> Option<&str> {
+        self.shortcuts.translate.as_deref()
+    }
+
     fn sanitize_shortcut(value: &str) -> Option<String> {
         let trimmed = value.trim();
         if trimmed.is_empty() {
@@ -763,6 +1620,23 @@ impl ConfigManager {
         candidates
     }
 
+    pub fn get_whisper_server_binary(&self, configured: Option<&str>) -> Option<PathBuf> {
+        if let Some(path) = configured {
+            return Some(expand_tilde(path));
+        }
+
+        let home = env::var("HOME").expect("HOME not set");
+        let local_dir = PathBuf::from(&home).join(".local/share/hyprwhspr-rs/whisper.cpp");
+
+        [
+            local_dir.join("build/bin/whisper-server"),
+            local_dir.join("whisper-server"),
+            PathBuf::from("/usr/bin/whisper-server"),
+        ]
+        .into_iter()
+        .find(|path| path.exists())
+    }
+
     pub fn get_temp_dir(&self) -> PathBuf {
         let data_dir = directories::ProjectDirs::from("", "", "hyprwhspr-rs")
             .expect("Failed to get data directory")
@@ -774,6 +1648,15 @@ impl ConfigManager {
         temp_dir
     }
 
+    /// Speech replacement pack files (`<name>.jsonc`), next to the config file
+    pub fn get_speech_packs_dir(&self) -> PathBuf {
+        self.inner
+            .config_path
+            .parent()
+            .map(|dir| dir.join("speech_packs"))
+            .unwrap_or_else(|| PathBuf::from("speech_packs"))
+    }
+
     pub fn get_assets_dir(&self) -> PathBuf {
         let install_path = PathBuf::from("/usr/lib/hyprwhspr-rs/share/assets");
         if install_path.exists() {
@@ -804,6 +1687,7 @@ impl ConfigManager {
             serde_json::from_value(value).context("Failed to deserialize config")?;
         config.migrate_legacy_transcription_settings();
         config.normalize_shortcuts();
+        config.validate_profiles();
         Ok(config)
     }
 
@@ -0,0 +1,464 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::cli::{CtlAction, CtlArgs, ProfileTarget};
+use crate::events::{DaemonEvent, DaemonState, EventBus};
+use crate::status::paths;
+
+/// Commands accepted on the control socket.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlCommand {
+    Start,
+    Stop,
+    Toggle,
+    Cancel,
+    Status,
+    /// Keep the connection open and stream [`DaemonEvent`]s as they happen.
+    Subscribe,
+    /// Select the profile after the active one (wrapping through no profile).
+    NextProfile,
+    PreviousProfile,
+    /// Select a profile by name, or none to use the base config.
+    SetProfile(Option<String>),
+}
+
+impl From<&CtlAction> for ControlCommand {
+    fn from(action: &CtlAction) -> Self {
+        match action {
+            CtlAction::Start => ControlCommand::Start,
+            CtlAction::Stop => ControlCommand::Stop,
+            CtlAction::Toggle => ControlCommand::Toggle,
+            CtlAction::Cancel => ControlCommand::Cancel,
+            CtlAction::Status => ControlCommand::Status,
+            CtlAction::Subscribe => ControlCommand::Subscribe,
+            CtlAction::Profile { target } => match ProfileTarget::parse(target) {
+                ProfileTarget::Next => ControlCommand::NextProfile,
+                ProfileTarget::Previous => ControlCommand::PreviousProfile,
+                ProfileTarget::None => ControlCommand::SetProfile(None),
+                ProfileTarget::Named(name) => ControlCommand::SetProfile(Some(name)),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlRequest {
+    pub command: ControlCommand,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub state: DaemonState,
+    pub provider: String,
+    /// Recordings waiting behind the transcription in flight
+    #[serde(default)]
+    pub queued: usize,
+    /// Profile selected with `active_profile` or `ctl profile`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlResponse {
+    pub ok: bool,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<DaemonStatus>,
+}
+
+impl ControlResponse {
+    pub fn success(message: impl Into<String>) -> Self {
+        Self {
+            ok: true,
+            message: message.into(),
+            status: None,
+        }
+    }
+
+    pub fn failure(message: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            message: message.into(),
+            status: None,
+        }
+    }
+
+    pub fn with_status(mut self, status: DaemonStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+}
+
+/// A request forwarded from the socket to the app loop, paired with a reply channel.
+pub struct ControlMessage {
+    pub command: ControlCommand,
+    pub reply: oneshot::Sender<ControlResponse>,
+}
+
+/// Listens on the control socket and forwards requests into the app loop.
+/// Removes the socket file when dropped.
+pub struct ControlServer {
+    socket_path: PathBuf,
+    handle: JoinHandle<()>,
+}
+
+impl ControlServer {
+    pub fn bind(tx: mpsc::Sender<ControlMessage>, events: EventBus) -> Result<Self> {
+        Self::bind_at(paths::control_socket(), tx, events)
+    }
+
+    pub fn bind_at(
+        socket_path: PathBuf,
+        tx: mpsc::Sender<ControlMessage>,
+        events: EventBus,
+    ) -> Result<Self> {
+        if let Some(parent) = socket_path.parent() {
+            fs::create_dir_all(parent).context("Failed to create control socket directory")?;
+        }
+
+        if socket_path.exists() {
+            if std::os::unix::net::UnixStream::connect(&socket_path).is_ok() {
+                return Err(anyhow!(
+                    "Another hyprwhspr-rs instance is already listening on {}",
+                    socket_path.display()
+                ));
+            }
+            debug!("Removing stale control socket at {}", socket_path.display());
+            fs::remove_file(&socket_path).with_context(|| {
+                format!(
+                    "Failed to remove stale control socket at {}",
+                    socket_path.display()
+                )
+            })?;
+        }
+
+        let listener = UnixListener::bind(&socket_path).with_context(|| {
+            format!("Failed to bind control socket at {}", socket_path.display())
+        })?;
+
+        info!("🎛️  Control socket listening at {}", socket_path.display());
+
+        let handle = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let tx = tx.clone();
+                        let events = events.clone();
+                        tokio::spawn(async move {
+                            if let Err(err) = serve_connection(stream, tx, events).await {
+                                debug!("Control connection ended with error: {:#}", err);
+                            }
+                        });
+                    }
+                    Err(err) => {
+                        warn!("Failed to accept control connection: {}", err);
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            socket_path,
+            handle,
+        })
+    }
+
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+        let _ = fs::remove_file(&self.socket_path);
+    }
+}
+
+async fn serve_connection(
+    stream: UnixStream,
+    tx: mpsc::Sender<ControlMessage>,
+    events: EventBus,
+) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<ControlRequest>(trimmed) {
+            Ok(ControlRequest {
+                command: ControlCommand::Subscribe,
+            }) => {
+                // Subscribe before acknowledging so no event slips between the two
+                let receiver = events.subscribe();
+                write_line(&mut writer, &ControlResponse::success("Subscribed")).await?;
+                return stream_events(receiver, writer).await;
+            }
+            Ok(request) => dispatch(request.command, &tx).await,
+            Err(err) => ControlResponse::failure(format!("Invalid request: {err}")),
+        };
+
+        write_line(&mut writer, &response).await?;
+    }
+
+    Ok(())
+}
+
+async fn stream_events(
+    mut receiver: broadcast::Receiver<DaemonEvent>,
+    mut writer: OwnedWriteHalf,
+) -> Result<()> {
+    loop {
+        match receiver.recv().await {
+            Ok(event) => write_line(&mut writer, &event).await?,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                warn!("Event subscriber lagged behind; dropped {} events", skipped);
+            }
+            Err(broadcast::error::RecvError::Closed) => return Ok(()),
+        }
+    }
+}
+
+async fn write_line<T: Serialize>(writer: &mut OwnedWriteHalf, value: &T) -> Result<()> {
+    let mut payload =
+        serde_json::to_string(value).context("Failed to serialize control message")?;
+    payload.push('\n');
+    writer
+        .write_all(payload.as_bytes())
+        .await
+        .context("Failed to write to control socket")
+}
+
+async fn dispatch(command: ControlCommand, tx: &mpsc::Sender<ControlMessage>) -> ControlResponse {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let message = ControlMessage {
+        command,
+        reply: reply_tx,
+    };
+
+    if tx.send(message).await.is_err() {
+        return ControlResponse::failure("Daemon is shutting down");
+    }
+
+    reply_rx
+        .await
+        .unwrap_or_else(|_| ControlResponse::failure("Daemon dropped the request"))
+}
+
+/// Sends a single command to the running daemon and waits for its reply.
+pub async fn send_command(command: ControlCommand) -> Result<ControlResponse> {
+    send_command_to(&paths::control_socket(), command).await
+}
+
+pub async fn send_command_to(
+    socket_path: &Path,
+    command: ControlCommand,
+) -> Result<ControlResponse> {
+    let (mut lines, _writer) = open_request(socket_path, command).await?;
+    read_response(&mut lines).await
+}
+
+type ResponseLines = Lines<BufReader<OwnedReadHalf>>;
+
+/// Live feed of daemon events from a `subscribe` request.
+pub struct EventStream {
+    lines: ResponseLines,
+    // Held so the daemon does not see the connection as closed
+    _writer: OwnedWriteHalf,
+}
+
+impl EventStream {
+    /// Waits for the next event; `None` once the daemon goes away.
+    pub async fn next(&mut self) -> Result<Option<DaemonEvent>> {
+        match self.lines.next_line().await? {
+            Some(line) => Ok(Some(
+                serde_json::from_str(&line).context("Failed to parse daemon event")?,
+            )),
+            None => Ok(None),
+        }
+    }
+}
+
+pub async fn subscribe() -> Result<EventStream> {
+    subscribe_to(&paths::control_socket()).await
+}
+
+pub async fn subscribe_to(socket_path: &Path) -> Result<EventStream> {
+    let (mut lines, writer) = open_request(socket_path, ControlCommand::Subscribe).await?;
+    let response = read_response(&mut lines).await?;
+    if !response.ok {
+        return Err(anyhow!(response.message));
+    }
+
+    Ok(EventStream {
+        lines,
+        _writer: writer,
+    })
+}
+
+async fn open_request(
+    socket_path: &Path,
+    command: ControlCommand,
+) -> Result<(ResponseLines, OwnedWriteHalf)> {
+    let stream = UnixStream::connect(socket_path).await.with_context(|| {
+        format!(
+            "Failed to connect to hyprwhspr-rs at {} (is the daemon running?)",
+            socket_path.display()
+        )
+    })?;
+
+    let (reader, mut writer) = stream.into_split();
+    write_line(&mut writer, &ControlRequest { command })
+        .await
+        .context("Failed to send control request")?;
+
+    Ok((BufReader::new(reader).lines(), writer))
+}
+
+async fn read_response(lines: &mut ResponseLines) -> Result<ControlResponse> {
+    let line = lines
+        .next_line()
+        .await
+        .context("Failed to read control response")?
+        .ok_or_else(|| anyhow!("Daemon closed the connection without replying"))?;
+
+    serde_json::from_str(&line).context("Failed to parse control response")
+}
+
+/// Entry point for `hyprwhspr-rs ctl <action>`.
+pub async fn run_ctl(args: &CtlArgs) -> Result<()> {
+    if let CtlAction::Subscribe = args.action {
+        let mut stream = subscribe().await?;
+        while let Some(event) = stream.next().await? {
+            println!("{}", serde_json::to_string(&event)?);
+        }
+        return Ok(());
+    }
+
+    let response = send_command(ControlCommand::from(&args.action)).await?;
+
+    if let Some(status) = &response.status {
+        println!("{}", serde_json::to_string(status)?);
+    } else if !response.message.is_empty() {
+        println!("{}", response.message);
+    }
+
+    if response.ok {
+        Ok(())
+    } else {
+        Err(anyhow!(response.message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::StopReason;
+
+    fn test_socket_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("hyprwhspr-rs-{}-{}.sock", name, std::process::id()))
+    }
+
+    #[test]
+    fn request_uses_snake_case_commands() {
+        let json = serde_json::to_string(&ControlRequest {
+            command: ControlCommand::Toggle,
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"command":"toggle"}"#);
+
+        let json = serde_json::to_string(&ControlRequest {
+            command: ControlCommand::SetProfile(Some("terminal".into())),
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"command":{"set_profile":"terminal"}}"#);
+    }
+
+    #[tokio::test]
+    async fn round_trips_command_through_socket() -> Result<()> {
+        let path = test_socket_path("roundtrip");
+        let (tx, mut rx) = mpsc::channel(4);
+        let server = ControlServer::bind_at(path.clone(), tx, EventBus::new())?;
+
+        tokio::spawn(async move {
+            while let Some(message) = rx.recv().await {
+                let response = match message.command {
+                    ControlCommand::Toggle => ControlResponse::success("Recording started"),
+                    _ => ControlResponse::failure("unsupported"),
+                };
+                let _ = message.reply.send(response);
+            }
+        });
+
+        let response = send_command_to(&path, ControlCommand::Toggle).await?;
+        assert!(response.ok);
+        assert_eq!(response.message, "Recording started");
+
+        let response = send_command_to(&path, ControlCommand::Stop).await?;
+        assert!(!response.ok);
+
+        drop(server);
+        assert!(!path.exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn refuses_to_steal_a_live_socket() -> Result<()> {
+        let path = test_socket_path("live");
+        let (tx, _rx) = mpsc::channel(1);
+        let _server = ControlServer::bind_at(path.clone(), tx.clone(), EventBus::new())?;
+        assert!(ControlServer::bind_at(path, tx, EventBus::new()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn events_are_tagged_json_lines() {
+        let json = serde_json::to_string(&DaemonEvent::RecordingStopped {
+            duration_ms: 1200,
+            reason: StopReason::Silence,
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            r#"{"event":"recording_stopped","duration_ms":1200,"reason":"silence"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn streams_published_events_to_subscribers() -> Result<()> {
+        let path = test_socket_path("subscribe");
+        let (tx, _rx) = mpsc::channel(1);
+        let events = EventBus::new();
+        let _server = ControlServer::bind_at(path.clone(), tx, events.clone())?;
+
+        let mut stream = subscribe_to(&path).await?;
+        events.publish(DaemonEvent::RecordingStarted);
+        events.publish(DaemonEvent::Transcription {
+            text: "hello".into(),
+            provider: "Groq".into(),
+        });
+
+        assert_eq!(stream.next().await?, Some(DaemonEvent::RecordingStarted));
+        assert_eq!(
+            stream.next().await?,
+            Some(DaemonEvent::Transcription {
+                text: "hello".into(),
+                provider: "Groq".into(),
+            })
+        );
+        Ok(())
+    }
+}
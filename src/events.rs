@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+/// Buffered events per subscriber before slow readers start lagging.
+const EVENT_CAPACITY: usize = 64;
+
+/// Coarse daemon state, as reported by `ctl status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DaemonState {
+    Idle,
+    Recording,
+    Processing,
+}
+
+/// Why a recording ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    /// A shortcut or control command stopped it.
+    Manual,
+    /// Trailing silence after speech (`fast_vad.auto_stop`).
+    Silence,
+    /// The recording reached `max_recording_secs`.
+    MaxDuration,
+}
+
+/// State changes published by the daemon, serialized as one JSON object per line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum DaemonEvent {
+    /// Idle and waiting for a shortcut or control command.
+    Ready,
+    RecordingStarted,
+    /// The recording will be stopped automatically in `remaining_secs`.
+    RecordingLimitApproaching {
+        remaining_secs: u32,
+    },
+    RecordingStopped {
+        duration_ms: u64,
+        reason: StopReason,
+    },
+    /// The recording or transcription in `stage` was discarded without injecting anything.
+    Cancelled {
+        stage: DaemonState,
+    },
+    Processing,
+    Transcription {
+        text: String,
+        provider: String,
+    },
+    Injected,
+    InjectionFailed {
+        message: String,
+    },
+    Error {
+        message: String,
+    },
+    ConfigReloaded {
+        provider: String,
+    },
+    /// A different profile was selected; `None` is the base config.
+    ProfileChanged {
+        profile: Option<String>,
+    },
+}
+
+/// Fan-out channel for [`DaemonEvent`]s. Cloning shares the same stream.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    tx: broadcast::Sender<DaemonEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(EVENT_CAPACITY);
+        Self { tx }
+    }
+
+    /// Publish an event; it is dropped silently when nobody is listening.
+    pub fn publish(&self, event: DaemonEvent) {
+        let _ = self.tx.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<DaemonEvent> {
+        self.tx.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
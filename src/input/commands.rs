@@ -0,0 +1,393 @@
+use crate::config::VoiceCommandsConfig;
+use anyhow::{anyhow, bail, Context, Result};
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyModifier {
+    Ctrl,
+    Shift,
+    Alt,
+    Super,
+}
+
+impl KeyModifier {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_uppercase().as_str() {
+            "CTRL" | "CONTROL" => Some(Self::Ctrl),
+            "SHIFT" => Some(Self::Shift),
+            "ALT" => Some(Self::Alt),
+            "SUPER" | "META" | "WIN" | "LOGO" => Some(Self::Super),
+            _ => None,
+        }
+    }
+
+    /// Modifier name for Hyprland `sendshortcut`
+    pub fn hyprland_name(self) -> &'static str {
+        match self {
+            Self::Ctrl => "CTRL",
+            Self::Shift => "SHIFT",
+            Self::Alt => "ALT",
+            Self::Super => "SUPER",
+        }
+    }
+}
+
+/// Keys a voice command can press. Kept to keys every injection backend can send.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamedKey {
+    Return,
+    Tab,
+    BackSpace,
+    Delete,
+    Escape,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Space,
+    /// A letter or digit
+    Char(char),
+}
+
+impl NamedKey {
+    fn parse(name: &str) -> Option<Self> {
+        let mut chars = name.chars();
+        if let (Some(ch), None) = (chars.next(), chars.next()) {
+            return ch
+                .is_ascii_alphanumeric()
+                .then(|| Self::Char(ch.to_ascii_lowercase()));
+        }
+
+        match name.to_ascii_lowercase().as_str() {
+            "return" | "enter" => Some(Self::Return),
+            "tab" => Some(Self::Tab),
+            "backspace" => Some(Self::BackSpace),
+            "delete" | "del" => Some(Self::Delete),
+            "escape" | "esc" => Some(Self::Escape),
+            "left" => Some(Self::Left),
+            "right" => Some(Self::Right),
+            "up" => Some(Self::Up),
+            "down" => Some(Self::Down),
+            "home" => Some(Self::Home),
+            "end" => Some(Self::End),
+            "page_up" | "pageup" | "prior" => Some(Self::PageUp),
+            "page_down" | "pagedown" | "next" => Some(Self::PageDown),
+            "space" => Some(Self::Space),
+            _ => None,
+        }
+    }
+
+    /// xkb keysym name, understood by Hyprland `sendshortcut` and wrtype
+    pub fn xkb_name(self) -> String {
+        match self {
+            Self::Return => "Return".to_string(),
+            Self::Tab => "Tab".to_string(),
+            Self::BackSpace => "BackSpace".to_string(),
+            Self::Delete => "Delete".to_string(),
+            Self::Escape => "Escape".to_string(),
+            Self::Left => "Left".to_string(),
+            Self::Right => "Right".to_string(),
+            Self::Up => "Up".to_string(),
+            Self::Down => "Down".to_string(),
+            Self::Home => "Home".to_string(),
+            Self::End => "End".to_string(),
+            Self::PageUp => "Page_Up".to_string(),
+            Self::PageDown => "Page_Down".to_string(),
+            Self::Space => "space".to_string(),
+            Self::Char(ch) => ch.to_string(),
+        }
+    }
+}
+
+/// A key pressed while holding modifiers, written like shortcuts: `CTRL+SHIFT+Z`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyChord {
+    pub modifiers: Vec<KeyModifier>,
+    pub key: NamedKey,
+}
+
+impl KeyChord {
+    pub fn parse(chord: &str) -> Result<Self> {
+        let parts: Vec<&str> = chord.split('+').map(str::trim).collect();
+        let Some((key, modifiers)) = parts.split_last() else {
+            bail!("Empty key chord");
+        };
+
+        let modifiers = modifiers
+            .iter()
+            .map(|name| {
+                KeyModifier::parse(name).ok_or_else(|| anyhow!("Unknown modifier: {}", name))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let key = NamedKey::parse(key).ok_or_else(|| anyhow!("Unknown key: {}", key))?;
+
+        Ok(Self { modifiers, key })
+    }
+
+    pub fn key(key: NamedKey) -> Self {
+        Self {
+            modifiers: Vec::new(),
+            key,
+        }
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for modifier in &self.modifiers {
+            write!(f, "{}+", modifier.hyprland_name())?;
+        }
+        write!(f, "{}", self.key.xkb_name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VoiceAction {
+    Keys(KeyChord),
+    /// Erase the text of the previous injection
+    DeleteLast,
+}
+
+impl VoiceAction {
+    fn parse(action: &str) -> Result<Self> {
+        let action = action.trim();
+        if action.eq_ignore_ascii_case("delete_last") {
+            return Ok(Self::DeleteLast);
+        }
+        KeyChord::parse(action).map(Self::Keys)
+    }
+
+    /// Whether the whitespace dictated around the command is kept: a Tab or an arrow sits
+    /// between words, a line break or a deletion doesn't
+    pub fn keeps_spacing(&self) -> bool {
+        matches!(self, Self::Keys(chord) if chord.key != NamedKey::Return)
+    }
+}
+
+impl fmt::Display for VoiceAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Keys(chord) => write!(f, "{}", chord),
+            Self::DeleteLast => write!(f, "delete last dictation"),
+        }
+    }
+}
+
+/// A transcript split into text to paste and commands to run, in spoken order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Piece {
+    Text(String),
+    Command { phrase: String, action: VoiceAction },
+}
+
+/// The configured command vocabulary, keyed by normalized phrase
+#[derive(Debug, Clone, Default)]
+pub struct VoiceCommands {
+    commands: HashMap<String, VoiceAction>,
+}
+
+impl VoiceCommands {
+    pub fn new(config: &VoiceCommandsConfig) -> Result<Self> {
+        if !config.enabled {
+            return Ok(Self::default());
+        }
+
+        let mut commands = HashMap::with_capacity(config.commands.len());
+        for (phrase, action) in &config.commands {
+            let key = normalize(phrase);
+            if key.is_empty() {
+                continue;
+            }
+            let action = VoiceAction::parse(action)
+                .with_context(|| format!("Invalid voice command \"{}\"", phrase))?;
+            commands.insert(key, action);
+        }
+
+        Ok(Self { commands })
+    }
+
+    /// Split `text` at sentences that are a command phrase and nothing else. Text next to a
+    /// command that [keeps spacing](VoiceAction::keeps_spacing) keeps the whitespace on
+    /// that side.
+    pub fn split(&self, text: &str) -> Vec<Piece> {
+        if self.commands.is_empty() {
+            return vec![Piece::Text(text.to_string())];
+        }
+
+        let mut pieces = Vec::new();
+        let mut text_start = 0;
+        for (start, end) in sentence_ranges(text) {
+            let sentence = &text[start..end];
+            let Some(action) = self.commands.get(&normalize(sentence)) else {
+                continue;
+            };
+
+            // The whitespace ahead of the command is in `sentence`, not the text before it
+            let phrase = sentence.trim_start();
+            let before = &text[text_start..end - phrase.len()];
+            push_text(&mut pieces, before, action.keeps_spacing());
+            pieces.push(Piece::Command {
+                phrase: phrase.trim_end().to_string(),
+                action: action.clone(),
+            });
+            text_start = end;
+        }
+        push_text(&mut pieces, &text[text_start..], false);
+
+        pieces
+    }
+}
+
+/// Add the text between two commands (or the transcript's edges), trimmed except where the
+/// neighbouring command keeps spacing
+fn push_text(pieces: &mut Vec<Piece>, text: &str, spaced_before_command: bool) {
+    if text.trim().is_empty() {
+        return;
+    }
+
+    let spaced_after_command = matches!(
+        pieces.last(),
+        Some(Piece::Command { action, .. }) if action.keeps_spacing()
+    );
+    let text = if spaced_after_command {
+        text
+    } else {
+        text.trim_start()
+    };
+    let text = if spaced_before_command {
+        text
+    } else {
+        text.trim_end()
+    };
+    pieces.push(Piece::Text(text.to_string()));
+}
+
+/// Byte ranges of the sentences in `text`, ending after `.`, `!`, `?` or `…` followed by
+/// whitespace, or at a line break
+fn sentence_ranges(text: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((index, ch)) = chars.next() {
+        let end = index + ch.len_utf8();
+        let at_boundary = ch == '\n'
+            || (matches!(ch, '.' | '!' | '?' | '…')
+                && chars.peek().is_none_or(|(_, next)| next.is_whitespace()));
+        if at_boundary {
+            ranges.push((start, end));
+            start = end;
+        }
+    }
+
+    if start < text.len() {
+        ranges.push((start, text.len()));
+    }
+    ranges
+}
+
+/// Lowercase words without punctuation, joined by single spaces
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|ch| ch.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commands() -> VoiceCommands {
+        VoiceCommands::new(&VoiceCommandsConfig {
+            enabled: true,
+            ..VoiceCommandsConfig::default()
+        })
+        .unwrap()
+    }
+
+    fn command(phrase: &str, action: VoiceAction) -> Piece {
+        Piece::Command {
+            phrase: phrase.to_string(),
+            action,
+        }
+    }
+
+    #[test]
+    fn commands_are_split_out_of_the_transcript() {
+        let pieces = commands().split("Fix the typo. Press enter. Then deploy it");
+        assert_eq!(
+            pieces,
+            [
+                Piece::Text("Fix the typo.".to_string()),
+                command(
+                    "Press enter.",
+                    VoiceAction::Keys(KeyChord::key(NamedKey::Return))
+                ),
+                Piece::Text("Then deploy it".to_string()),
+            ]
+        );
+
+        assert_eq!(
+            commands().split("Scratch that!"),
+            [command("Scratch that!", VoiceAction::DeleteLast)]
+        );
+    }
+
+    #[test]
+    fn text_keeps_its_spacing_next_to_commands_other_than_enter() {
+        assert_eq!(
+            commands().split("foo. Press tab. bar"),
+            [
+                Piece::Text("foo. ".to_string()),
+                command(
+                    "Press tab.",
+                    VoiceAction::Keys(KeyChord::key(NamedKey::Tab))
+                ),
+                Piece::Text(" bar".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn commands_inside_a_sentence_are_plain_text() {
+        let text = "I want to undo that change and select all of it.";
+        assert_eq!(commands().split(text), [Piece::Text(text.to_string())]);
+    }
+
+    #[test]
+    fn chords_parse_like_shortcuts() {
+        let chord = KeyChord::parse("ctrl+shift+z").unwrap();
+        assert_eq!(chord.modifiers, [KeyModifier::Ctrl, KeyModifier::Shift]);
+        assert_eq!(chord.key, NamedKey::Char('z'));
+        assert_eq!(chord.to_string(), "CTRL+SHIFT+z");
+
+        assert_eq!(KeyChord::parse("Enter").unwrap().key, NamedKey::Return);
+        assert!(KeyChord::parse("HYPER+A").is_err());
+        assert!(KeyChord::parse("F13").is_err());
+    }
+
+    #[test]
+    fn disabled_commands_leave_text_alone() {
+        let config = VoiceCommandsConfig {
+            enabled: false,
+            ..VoiceCommandsConfig::default()
+        };
+        let commands = VoiceCommands::new(&config).unwrap();
+        assert_eq!(
+            commands.split("Press enter."),
+            [Piece::Text("Press enter.".to_string())]
+        );
+    }
+}
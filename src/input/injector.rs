@@ -1,13 +1,18 @@
+use crate::config::{Config, PasteMethod, VoiceCommandsConfig};
+use crate::input::commands::{KeyChord, KeyModifier, NamedKey, Piece, VoiceAction, VoiceCommands};
+use crate::input::overrides::WordOverrides;
+use crate::input::replacements::SpeechReplacements;
 use crate::logging::{record_text_pipeline, PipelineStepRecord, TextPipelineRecord};
 use anyhow::{anyhow, Context, Result};
 use arboard::Clipboard;
 use enigo::{Enigo, Keyboard, Settings};
 use regex::Regex;
 use serde_json::Value;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 use std::env;
 use std::path::PathBuf;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::UnixStream;
 use tracing::{debug, info, warn};
@@ -67,6 +72,14 @@ static MERGE_SYMBOL_PATTERNS: LazyLock<Vec<(char, Regex)>> = LazyLock::new(|| {
 static UNDERSCORE_BRIDGE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"([^\s_])\s+(_+)\s+([^\s_])").expect("valid underscore bridge regex")
 });
+static CASE_OPERATOR_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\bformat\s+(?P<style>(?:screaming[ -]?snake|snake|camel|pascal|kebab|constant)[ -]?case|all[ -]?caps|no[ -]?spaces?)\b[,:]?\s*",
+    )
+    .expect("valid case operator regex")
+});
+static CASE_END_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bend[ -]?case\b[,:]?").expect("valid case end regex"));
 
 const SHIFT_PASTE_CLASSES: &[&str] = &[
     "Alacritty",
@@ -112,6 +125,25 @@ struct HyprlandDispatcher {
     socket_path: PathBuf,
 }
 
+/// The focused Hyprland window
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActiveWindow {
+    pub class: Option<String>,
+    pub title: Option<String>,
+}
+
+/// The focused window, or `None` outside Hyprland or when it cannot be queried
+pub async fn focused_window() -> Option<ActiveWindow> {
+    let dispatcher = HyprlandDispatcher::new()?;
+    match dispatcher.active_window().await {
+        Ok(window) => Some(window),
+        Err(err) => {
+            warn!("Failed to query Hyprland active window: {err:?}");
+            None
+        }
+    }
+}
+
 impl HyprlandDispatcher {
     fn new() -> Option<Self> {
         let runtime_dir = env::var("XDG_RUNTIME_DIR").ok()?;
@@ -155,7 +187,7 @@ impl HyprlandDispatcher {
         };
         let target_segment = target.map(|t| format!(", {t}")).unwrap_or_default();
         let command = if mods_segment.is_empty() {
-            format!("dispatch sendshortcut {key}{target_segment}")
+            format!("dispatch sendshortcut , {key}{target_segment}")
         } else {
             format!("dispatch sendshortcut {mods_segment}, {key}{target_segment}")
         };
@@ -167,6 +199,16 @@ impl HyprlandDispatcher {
         }
     }
 
+    async fn active_window(&self) -> Result<ActiveWindow> {
+        let response = self.send_command("j/activewindow").await?;
+        let title = Self::extract_window_title_from_response(&response);
+        let class = match Self::handle_activewindow_response("j/activewindow", &response, true)? {
+            Some(class) => Some(class),
+            None => self.active_window_class().await?,
+        };
+        Ok(ActiveWindow { class, title })
+    }
+
     async fn active_window_class(&self) -> Result<Option<String>> {
         // Try JSON-formatted activewindow first for newer Hyprland releases.
         let json_response = self.send_command("j/activewindow").await?;
@@ -322,6 +364,22 @@ impl HyprlandDispatcher {
         Err(anyhow!("No class entry found in Hyprland response"))
     }
 
+    fn extract_window_title_from_response(response: &str) -> Option<String> {
+        if let Ok(value) = serde_json::from_str::<Value>(response) {
+            return value
+                .get("title")
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+        }
+
+        response.lines().find_map(|line| {
+            let (key, value) = line.trim().split_once(':')?;
+            key.trim()
+                .eq_ignore_ascii_case("title")
+                .then(|| value.trim().to_string())
+        })
+    }
+
     fn extract_class_from_clients_json(text: &str, address: &str) -> Option<String> {
         let Ok(value) = serde_json::from_str::<Value>(text) else {
             return None;
@@ -418,319 +476,6 @@ impl HyprlandDispatcher {
     }
 }
 
-#[derive(Clone, Copy)]
-struct SpeechReplacement {
-    phrase: &'static str,
-    replacement: &'static str,
-    adjust_preceding_punct: bool,
-}
-
-static SPEECH_REPLACEMENTS: &[SpeechReplacement] = &[
-    SpeechReplacement {
-        phrase: "period",
-        replacement: ".",
-        adjust_preceding_punct: true,
-    },
-    SpeechReplacement {
-        phrase: "comma",
-        replacement: ",",
-        adjust_preceding_punct: true,
-    },
-    SpeechReplacement {
-        phrase: "question mark",
-        replacement: "?",
-        adjust_preceding_punct: true,
-    },
-    SpeechReplacement {
-        phrase: "exclamation mark",
-        replacement: "!",
-        adjust_preceding_punct: true,
-    },
-    SpeechReplacement {
-        phrase: "exclamation point",
-        replacement: "!",
-        adjust_preceding_punct: true,
-    },
-    SpeechReplacement {
-        phrase: "colon",
-        replacement: ":",
-        adjust_preceding_punct: true,
-    },
-    SpeechReplacement {
-        phrase: "semicolon",
-        replacement: ";",
-        adjust_preceding_punct: true,
-    },
-    SpeechReplacement {
-        phrase: "new line",
-        replacement: "\n",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "tab",
-        replacement: "\t",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "dash",
-        replacement: "-",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "dash dash",
-        replacement: "--",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "hyphen",
-        replacement: "-",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "underscore",
-        replacement: "_",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "open paren",
-        replacement: "(",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "open parenthesis",
-        replacement: "(",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "open parentheses",
-        replacement: "(",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "close paren",
-        replacement: ")",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "close parenthesis",
-        replacement: ")",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "close parentheses",
-        replacement: ")",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "open bracket",
-        replacement: "[",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "close bracket",
-        replacement: "]",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "open brace",
-        replacement: "{",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "close brace",
-        replacement: "}",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "at symbol",
-        replacement: "@",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "hash",
-        replacement: "#",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "dollar sign",
-        replacement: "$",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "percent",
-        replacement: "%",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "caret",
-        replacement: "^",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "ampersand",
-        replacement: "&",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "asterisk",
-        replacement: "*",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "plus",
-        replacement: "+",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "equals",
-        replacement: "=",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "less than",
-        replacement: "<",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "greater than",
-        replacement: ">",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "slash",
-        replacement: "/",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "backslash",
-        replacement: "\\",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "pipe",
-        replacement: "|",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "tilde",
-        replacement: "~",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "grave",
-        replacement: "`",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "quote",
-        replacement: "\"",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "double quote",
-        replacement: "\"",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "apostrophe",
-        replacement: "'",
-        adjust_preceding_punct: false,
-    },
-    SpeechReplacement {
-        phrase: "single quote",
-        replacement: "'",
-        adjust_preceding_punct: false,
-    },
-];
-
-static SPEECH_REPLACEMENT_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    let mut entries: Vec<&SpeechReplacement> = SPEECH_REPLACEMENTS.iter().collect();
-    entries.sort_by(|a, b| b.phrase.len().cmp(&a.phrase.len()));
-
-    let alternates = entries
-        .into_iter()
-        .map(|entry| regex::escape(entry.phrase))
-        .collect::<Vec<_>>()
-        .join("|");
-
-    let pattern = format!(r"(?i)\b(?P<command>{})\b[.!?,;:]*", alternates);
-    Regex::new(&pattern).expect("valid speech replacement regex")
-});
-
-static SPEECH_REPLACEMENT_LOOKUP: LazyLock<HashMap<&'static str, &'static SpeechReplacement>> =
-    LazyLock::new(|| {
-        let mut map = HashMap::new();
-        for entry in SPEECH_REPLACEMENTS {
-            map.insert(entry.phrase, entry);
-        }
-        map
-    });
-
-fn apply_speech_replacements(text: &str) -> (String, usize) {
-    let mut result = String::with_capacity(text.len());
-    let mut last_end = 0;
-    let mut count = 0;
-
-    for caps in SPEECH_REPLACEMENT_REGEX.captures_iter(text) {
-        let matched = caps.get(0).expect("regex match");
-        result.push_str(&text[last_end..matched.start()]);
-
-        if let Some(command) = caps.name("command") {
-            let key = command.as_str().to_ascii_lowercase();
-            if let Some(entry) = SPEECH_REPLACEMENT_LOOKUP.get(key.as_str()) {
-                apply_speech_replacement_entry(&mut result, entry);
-                count += 1;
-            }
-        }
-
-        last_end = matched.end();
-    }
-
-    result.push_str(&text[last_end..]);
-    (result, count)
-}
-
-fn sanitize_word_overrides(mut overrides: HashMap<String, String>) -> HashMap<String, String> {
-    overrides.retain(|key, _| !key.eq_ignore_ascii_case("em dash"));
-    overrides
-}
-
-fn apply_speech_replacement_entry(buffer: &mut String, entry: &SpeechReplacement) {
-    if entry.adjust_preceding_punct {
-        let mut trailing_ws: Vec<char> = Vec::new();
-
-        loop {
-            if buffer.ends_with(' ') {
-                buffer.pop();
-                trailing_ws.push(' ');
-            } else if buffer.ends_with('\t') {
-                buffer.pop();
-                trailing_ws.push('\t');
-            } else {
-                break;
-            }
-        }
-
-        loop {
-            let Some(ch) = buffer.chars().last() else {
-                break;
-            };
-            if matches!(ch, '.' | ',' | '!' | '?' | ';' | ':') {
-                buffer.pop();
-            } else {
-                break;
-            }
-        }
-
-        buffer.push_str(entry.replacement);
-        for ch in trailing_ws.into_iter().rev() {
-            buffer.push(ch);
-        }
-    } else {
-        buffer.push_str(entry.replacement);
-    }
-}
-
 fn capitalize_after_period(input: &str) -> (String, usize) {
     let mut result = String::with_capacity(input.len());
     let mut capitalize_next = true;
@@ -823,6 +568,146 @@ fn collapse_underscore_spacing(input: &str) -> (String, usize) {
     (current, total_count)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CaseStyle {
+    Snake,
+    ScreamingSnake,
+    Camel,
+    Pascal,
+    Kebab,
+    AllCaps,
+    NoSpace,
+}
+
+impl CaseStyle {
+    fn from_operator(operator: &str) -> Self {
+        let operator = operator.to_ascii_lowercase();
+        if operator.starts_with("screaming") || operator.starts_with("constant") {
+            Self::ScreamingSnake
+        } else if operator.starts_with("snake") {
+            Self::Snake
+        } else if operator.starts_with("camel") {
+            Self::Camel
+        } else if operator.starts_with("pascal") {
+            Self::Pascal
+        } else if operator.starts_with("kebab") {
+            Self::Kebab
+        } else if operator.starts_with("all") {
+            Self::AllCaps
+        } else {
+            Self::NoSpace
+        }
+    }
+
+    fn format(self, words: &[&str]) -> String {
+        match self {
+            Self::Snake => join_mapped(words, "_", str::to_lowercase),
+            Self::ScreamingSnake => join_mapped(words, "_", str::to_uppercase),
+            Self::Kebab => join_mapped(words, "-", str::to_lowercase),
+            Self::AllCaps => join_mapped(words, " ", str::to_uppercase),
+            Self::NoSpace => words.concat(),
+            Self::Pascal => join_mapped(words, "", capitalize_word),
+            Self::Camel => {
+                let mut formatted = words
+                    .first()
+                    .map(|word| word.to_lowercase())
+                    .unwrap_or_default();
+                formatted.push_str(&join_mapped(&words[1..], "", capitalize_word));
+                formatted
+            }
+        }
+    }
+}
+
+fn join_mapped(words: &[&str], separator: &str, map: impl Fn(&str) -> String) -> String {
+    words
+        .iter()
+        .map(|word| map(word))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn capitalize_word(word: &str) -> String {
+    let lower = word.to_lowercase();
+    let mut chars = lower.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// End of a case operator's scope: a punctuation mark (a period only when it ends a
+/// sentence, so `file.txt` stays intact) or a line break
+fn case_scope_boundary(text: &str) -> usize {
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, ch)) = chars.next() {
+        let boundary = match ch {
+            ',' | ';' | ':' | '!' | '?' | '\n' => true,
+            '.' => chars.peek().is_none_or(|(_, next)| next.is_whitespace()),
+            _ => false,
+        };
+        if boundary {
+            return index;
+        }
+    }
+    text.len()
+}
+
+/// Spoken identifier formatting: "format snake case user account id" becomes
+/// `user_account_id`.
+///
+/// An operator ("format" followed by snake, screaming snake/constant, camel, pascal or
+/// kebab case, all caps or no space) applies to the words after it up to the next punctuation mark, line break or
+/// operator, or up to a spoken "end case", which is dropped. Dashes and underscores
+/// already in those words split them too. An operator with no words after it is kept as
+/// spoken.
+fn apply_case_formatting(input: &str) -> (String, usize) {
+    let mut result = String::with_capacity(input.len());
+    let mut rest = input;
+    let mut count = 0;
+
+    while let Some(caps) = CASE_OPERATOR_REGEX.captures(rest) {
+        let operator = caps.get(0).expect("regex match");
+        let style = CaseStyle::from_operator(&caps["style"]);
+        result.push_str(&rest[..operator.start()]);
+        let after = &rest[operator.end()..];
+
+        let mut scope_end = case_scope_boundary(after);
+        let mut resume = scope_end;
+        if let Some(end) = CASE_END_REGEX.find(&after[..scope_end]) {
+            scope_end = end.start();
+            resume = end.end();
+        }
+        if let Some(next) = CASE_OPERATOR_REGEX.find(&after[..scope_end]) {
+            scope_end = next.start();
+            resume = next.start();
+        }
+
+        let scope = &after[..scope_end];
+        let words: Vec<&str> = scope
+            .split(|ch: char| ch.is_whitespace() || ch == '_' || ch == '-')
+            .filter(|word| !word.is_empty())
+            .collect();
+
+        if words.is_empty() {
+            result.push_str(operator.as_str());
+            rest = after;
+            continue;
+        }
+
+        result.push_str(&style.format(&words));
+        if resume == scope_end {
+            // Keep the gap before the next operator; a dropped "end case" leaves its own
+            result.push_str(&scope[scope.trim_end().len()..]);
+        }
+        count += 1;
+        rest = &after[resume..];
+    }
+
+    result.push_str(rest);
+    (result, count)
+}
+
 fn trim_spaces_around_newlines(input: &str) -> (String, usize) {
     let mut count = 0;
 
@@ -843,34 +728,64 @@ fn trim_spaces_around_newlines(input: &str) -> (String, usize) {
     (final_result, count)
 }
 
+/// Characters pasted by the last injection, erased by a `delete_last` command. Injectors
+/// rebuilt for new settings share it, so "scratch that" still reaches the text pasted before.
+#[derive(Debug, Clone, Default)]
+pub struct UndoState(Arc<Mutex<Option<usize>>>);
+
+impl UndoState {
+    fn set(&self, chars: Option<usize>) {
+        *self.0.lock().expect("undo state lock poisoned") = chars;
+    }
+
+    fn take(&self) -> Option<usize> {
+        self.0.lock().expect("undo state lock poisoned").take()
+    }
+}
+
 pub struct TextInjector {
     enigo: Enigo,
     clipboard: Clipboard,
-    word_overrides: HashMap<String, String>,
+    word_overrides: WordOverrides,
     extra_shift_classes: HashSet<String>,
     default_shift_paste: bool,
     global_paste_shortcut: bool,
+    paste_method: PasteMethod,
+    /// Window classes that get the text typed instead of pasted
+    type_classes: HashSet<String>,
+    /// Pause between typed characters
+    type_delay: Duration,
+    /// Appended to every injection
+    trailing_whitespace: String,
     hyprland_dispatcher: Option<HyprlandDispatcher>,
     wrtype_client: Option<WrtypeClient>,
     wrtype_attempted: bool,
     wayland_env: bool,
     wayland_clipboard_enabled: bool,
+    voice_commands: VoiceCommands,
+    /// Run the voice commands found in dictation, not only in command-only recordings
+    inline_commands: bool,
+    /// Apply spoken "format ... case" operators
+    case_formatting: bool,
+    speech_replacements: SpeechReplacements,
+    undo: UndoState,
 }
 
 impl TextInjector {
-    pub fn new(
-        shift_paste_default: bool,
-        global_paste_shortcut: bool,
-        extra_shift_classes: Vec<String>,
-        word_overrides: HashMap<String, String>,
-        _auto_copy_clipboard: bool,
-    ) -> Result<Self> {
+    pub fn new(config: &Config, speech_replacements: SpeechReplacements) -> Result<Self> {
         let enigo = Enigo::new(&Settings::default())
             .context("Failed to initialize Enigo for text injection")?;
 
         let clipboard = Clipboard::new().context("Failed to initialize clipboard")?;
 
-        let sanitized_overrides = sanitize_word_overrides(word_overrides);
+        let word_overrides =
+            WordOverrides::new(config.word_overrides.clone(), &config.word_override_rules)
+                .context("Failed to compile word overrides")?;
+        // Command-only recordings use the vocabulary even when `enabled` is off
+        let voice_commands = VoiceCommands::new(&VoiceCommandsConfig {
+            enabled: true,
+            ..config.voice_commands.clone()
+        })?;
         let wayland_env = env::var("WAYLAND_DISPLAY").is_ok();
         let hyprland_dispatcher = HyprlandDispatcher::new();
 
@@ -883,45 +798,157 @@ impl TextInjector {
         Ok(Self {
             enigo,
             clipboard,
-            word_overrides: sanitized_overrides,
-            extra_shift_classes: extra_shift_classes
-                .into_iter()
+            word_overrides,
+            extra_shift_classes: config
+                .paste_hints
+                .shift
+                .iter()
                 .map(|entry| entry.trim().to_ascii_lowercase())
                 .filter(|entry| !entry.is_empty())
                 .collect(),
-            default_shift_paste: shift_paste_default,
-            global_paste_shortcut,
+            default_shift_paste: config.shift_paste,
+            global_paste_shortcut: config.global_paste_shortcut,
+            paste_method: config.paste_method,
+            type_classes: config
+                .paste_hints
+                .type_text
+                .iter()
+                .map(|entry| entry.trim().to_ascii_lowercase())
+                .filter(|entry| !entry.is_empty())
+                .collect(),
+            type_delay: Duration::from_millis(config.type_delay_ms.into()),
+            trailing_whitespace: config.trailing_whitespace.clone(),
             hyprland_dispatcher,
             wrtype_client: None,
             wrtype_attempted: false,
             wayland_env,
             wayland_clipboard_enabled: wayland_env,
+            voice_commands,
+            inline_commands: config.voice_commands.enabled,
+            case_formatting: config.voice_commands.case_formatting,
+            speech_replacements,
+            undo: UndoState::default(),
         })
     }
 
+    /// Share `undo` with the injector this one replaces
+    pub fn with_undo_state(mut self, undo: UndoState) -> Self {
+        self.undo = undo;
+        self
+    }
+
     pub async fn inject_text(&mut self, text: &str) -> Result<()> {
+        self.inject(text, false).await
+    }
+
+    /// Inject text that continues the previous injection, separated from it by a space
+    pub async fn inject_continuation(&mut self, text: &str) -> Result<()> {
+        self.inject(text, true).await
+    }
+
+    /// Run the voice commands spoken in `text` and paste none of it
+    pub async fn run_commands(&mut self, text: &str) -> Result<()> {
+        let mut ran_any = false;
+        for piece in self.voice_commands.split(text) {
+            match piece {
+                Piece::Command { phrase, action } => {
+                    info!("🗣️  Voice command \"{}\": {}", phrase, action);
+                    self.run_voice_action(&action).await?;
+                    ran_any = true;
+                }
+                Piece::Text(text) => debug!("Not a voice command, skipped: \"{}\"", text),
+            }
+        }
+
+        if !ran_any {
+            warn!("No voice command recognised in \"{}\"", text.trim());
+        }
+        Ok(())
+    }
+
+    async fn inject(&mut self, text: &str, continuation: bool) -> Result<()> {
         if text.trim().is_empty() {
             debug!("No text to inject (empty or whitespace)");
             return Ok(());
         }
 
+        if !self.inline_commands {
+            return self.paste_text(text.trim(), continuation).await;
+        }
+
+        let pieces = self.voice_commands.split(text);
+        if let [Piece::Text(_)] = pieces.as_slice() {
+            return self.paste_text(text.trim(), continuation).await;
+        }
+
+        for (index, piece) in pieces.into_iter().enumerate() {
+            match piece {
+                Piece::Text(text) => self.paste_text(&text, continuation && index == 0).await?,
+                Piece::Command { phrase, action } => {
+                    info!("🗣️  Voice command \"{}\": {}", phrase, action);
+                    self.run_voice_action(&action).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn paste_text(&mut self, text: &str, continuation: bool) -> Result<()> {
         // Preprocess text
-        let processed = self.preprocess_text(text);
+        let window_class =
+            if self.word_overrides.has_window_scoped_rules() || !self.type_classes.is_empty() {
+                self.focused_window_class().await
+            } else {
+                None
+            };
+        let processed = self.preprocess_text(text, window_class.as_deref());
 
         if processed.is_empty() {
             debug!("Text became empty after preprocessing, nothing to inject");
             return Ok(());
         }
 
+        let processed = spaced(text, processed, continuation, &self.trailing_whitespace);
+
         info!("Injecting text: {} characters", processed.len());
 
+        let typed = self.paste_method == PasteMethod::Type
+            || window_class
+                .as_deref()
+                .is_some_and(|class| self.type_classes.contains(&class.to_ascii_lowercase()));
+        if typed {
+            self.type_text(&processed).await?;
+        } else {
+            self.paste(&processed).await?;
+        }
+        self.undo.set(Some(processed.chars().count()));
+        Ok(())
+    }
+
+    /// Class of the focused window, when Hyprland can tell us
+    async fn focused_window_class(&self) -> Option<String> {
+        let dispatcher = self.hyprland_dispatcher.as_ref()?;
+        match dispatcher.active_window_class().await {
+            Ok(class) => class,
+            Err(err) => {
+                warn!("Failed to query Hyprland active window class: {err:?}");
+                None
+            }
+        }
+    }
+
+    async fn paste(&mut self, processed: &str) -> Result<()> {
         // Copy to clipboard using available backends
-        self.copy_processed_text(&processed)?;
+        self.copy_processed_text(processed)?;
 
         // Small delay to ensure window focus is ready for input (especially on Wayland/XWayland)
         tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
 
-        let use_global_paste = self.global_paste_shortcut;
+        let use_global_paste = match self.paste_method {
+            PasteMethod::Auto | PasteMethod::Type => self.global_paste_shortcut,
+            PasteMethod::ShiftInsert => true,
+            PasteMethod::CtrlV | PasteMethod::CtrlShiftV => false,
+        };
 
         if use_global_paste {
             // Universal paste mode: use Shift+Insert across all backends
@@ -957,32 +984,39 @@ impl TextInjector {
         }
 
         // Window-based paste mode: use Ctrl+V or Ctrl+Shift+V based on window class
-        let mut shift_hint: Option<bool> = None;
+        let mut shift_hint = match self.paste_method {
+            PasteMethod::CtrlV => Some(false),
+            PasteMethod::CtrlShiftV => Some(true),
+            PasteMethod::Auto | PasteMethod::ShiftInsert | PasteMethod::Type => None,
+        };
         let default_shift = self.default_shift_paste;
 
         if let Some(dispatcher) = self.hyprland_dispatcher.as_ref() {
-            match dispatcher.active_window_class().await {
-                Ok(class_opt) => {
-                    if let Some(class) = class_opt {
-                        if let Some(needs_shift) =
-                            shift_hint_for_class(&class, &self.extra_shift_classes)
-                        {
-                            debug!(
-                                class = class.as_str(),
-                                needs_shift, "Hyprland active window classification"
-                            );
-                            shift_hint = Some(needs_shift);
-                        } else {
-                            debug!(
-                                class = class.as_str(),
-                                default = default_shift,
-                                "Hyprland active window classification has no explicit shift rule"
-                            );
+            // An explicit paste_method needs no window classification
+            if shift_hint.is_none() {
+                match dispatcher.active_window_class().await {
+                    Ok(class_opt) => {
+                        if let Some(class) = class_opt {
+                            if let Some(needs_shift) =
+                                shift_hint_for_class(&class, &self.extra_shift_classes)
+                            {
+                                debug!(
+                                    class = class.as_str(),
+                                    needs_shift, "Hyprland active window classification"
+                                );
+                                shift_hint = Some(needs_shift);
+                            } else {
+                                debug!(
+                                    class = class.as_str(),
+                                    default = default_shift,
+                                    "Hyprland active window classification has no explicit shift rule"
+                                );
+                            }
                         }
                     }
-                }
-                Err(err) => {
-                    warn!("Failed to query Hyprland active window class: {err:?}");
+                    Err(err) => {
+                        warn!("Failed to query Hyprland active window class: {err:?}");
+                    }
                 }
             }
 
@@ -1018,6 +1052,197 @@ impl TextInjector {
         self.inject_via_enigo_shift_paste()
     }
 
+    async fn run_voice_action(&mut self, action: &VoiceAction) -> Result<()> {
+        match action {
+            VoiceAction::Keys(chord) => {
+                self.undo.set(None);
+                self.send_keys(chord, 1).await
+            }
+            VoiceAction::DeleteLast => match self.undo.take() {
+                Some(chars) => {
+                    self.send_keys(&KeyChord::key(NamedKey::BackSpace), chars)
+                        .await
+                }
+                None => {
+                    info!("Nothing to delete; no dictation was injected since the last command");
+                    Ok(())
+                }
+            },
+        }
+    }
+
+    /// Type `text` as key events instead of pasting it, so the clipboard is left alone
+    async fn type_text(&mut self, text: &str) -> Result<()> {
+        // Small delay to ensure window focus is ready for input (especially on Wayland/XWayland)
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+        let delay = self.type_delay;
+        let mut typed = 0;
+        if let Some(client) = self.ensure_wrtype_client() {
+            let mut failed = false;
+            for chunk in typing_chunks(text, delay) {
+                if let Err(err) = client.type_text(chunk) {
+                    warn!("Wayland virtual keyboard typing failed: {err:?}");
+                    failed = true;
+                    break;
+                }
+                typed += chunk.len();
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+            }
+
+            if !failed {
+                info!("✅ Text typed via Wayland virtual keyboard");
+                return Ok(());
+            }
+            self.invalidate_wrtype_client();
+        }
+
+        // Only what the virtual keyboard did not get to
+        debug!("Falling back to typing via Enigo");
+        self.type_via_enigo(&text[typed..]).await
+    }
+
+    async fn type_via_enigo(&mut self, text: &str) -> Result<()> {
+        use enigo::{Direction, Key};
+
+        let delay = self.type_delay;
+        for (index, line) in text.split('\n').enumerate() {
+            if index > 0 {
+                self.enigo
+                    .key(Key::Return, Direction::Click)
+                    .context("Failed to type a line break")?;
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+            }
+            for chunk in typing_chunks(line, delay) {
+                self.enigo.text(chunk).context("Failed to type text")?;
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+
+        info!("✅ Text typed via Enigo");
+        Ok(())
+    }
+
+    /// Press `chord` `times` times in the focused window, trying Hyprland, the Wayland
+    /// virtual keyboard and Enigo in the same order as pasting
+    async fn send_keys(&mut self, chord: &KeyChord, times: usize) -> Result<()> {
+        if times == 0 {
+            return Ok(());
+        }
+
+        if let Some(dispatcher) = self.hyprland_dispatcher.as_ref() {
+            let modifiers: Vec<&str> = chord
+                .modifiers
+                .iter()
+                .map(|modifier| modifier.hyprland_name())
+                .collect();
+            let key = chord.key.xkb_name();
+            let mut sent = Ok(());
+            for _ in 0..times {
+                sent = dispatcher
+                    .send_shortcut(&modifiers, &key, Some("active"))
+                    .await;
+                if sent.is_err() {
+                    break;
+                }
+            }
+            match sent {
+                Ok(_) => {
+                    debug!(%chord, times, "Keys sent via Hyprland sendshortcut");
+                    return Ok(());
+                }
+                Err(err) => warn!("Hyprland sendshortcut for {chord} failed: {err:?}"),
+            }
+        }
+
+        if let Some(client) = self.ensure_wrtype_client() {
+            let modifiers: Vec<Modifier> = chord
+                .modifiers
+                .iter()
+                .map(|modifier| match modifier {
+                    KeyModifier::Ctrl => Modifier::Ctrl,
+                    KeyModifier::Shift => Modifier::Shift,
+                    KeyModifier::Alt => Modifier::Alt,
+                    KeyModifier::Super => Modifier::Logo,
+                })
+                .collect();
+            let key = chord.key.xkb_name();
+            match (0..times).try_for_each(|_| client.send_shortcut(&modifiers, &key)) {
+                Ok(_) => {
+                    debug!(%chord, times, "Keys sent via Wayland virtual keyboard");
+                    return Ok(());
+                }
+                Err(err) => {
+                    warn!("Wayland virtual keyboard keys for {chord} failed: {err:?}");
+                    self.invalidate_wrtype_client();
+                }
+            }
+        }
+
+        debug!("Falling back to Enigo for {chord}");
+        self.send_keys_via_enigo(chord, times)
+    }
+
+    fn send_keys_via_enigo(&mut self, chord: &KeyChord, times: usize) -> Result<()> {
+        use enigo::{Direction, Key};
+
+        let modifiers: Vec<Key> = chord
+            .modifiers
+            .iter()
+            .map(|modifier| match modifier {
+                KeyModifier::Ctrl => Key::Control,
+                KeyModifier::Shift => Key::Shift,
+                KeyModifier::Alt => Key::Alt,
+                KeyModifier::Super => Key::Meta,
+            })
+            .collect();
+        let key = match chord.key {
+            NamedKey::Return => Key::Return,
+            NamedKey::Tab => Key::Tab,
+            NamedKey::BackSpace => Key::Backspace,
+            NamedKey::Delete => Key::Delete,
+            NamedKey::Escape => Key::Escape,
+            NamedKey::Left => Key::LeftArrow,
+            NamedKey::Right => Key::RightArrow,
+            NamedKey::Up => Key::UpArrow,
+            NamedKey::Down => Key::DownArrow,
+            NamedKey::Home => Key::Home,
+            NamedKey::End => Key::End,
+            NamedKey::PageUp => Key::PageUp,
+            NamedKey::PageDown => Key::PageDown,
+            NamedKey::Space => Key::Space,
+            NamedKey::Char(ch) => Key::Unicode(ch),
+        };
+
+        for modifier in &modifiers {
+            self.enigo
+                .key(*modifier, Direction::Press)
+                .with_context(|| format!("Failed to press {modifier:?}"))?;
+        }
+        let mut clicked = Ok(());
+        for _ in 0..times {
+            clicked = self.enigo.key(key, Direction::Click);
+            if clicked.is_err() {
+                break;
+            }
+        }
+        for modifier in modifiers.iter().rev() {
+            self.enigo
+                .key(*modifier, Direction::Release)
+                .with_context(|| format!("Failed to release {modifier:?}"))?;
+        }
+        clicked.with_context(|| format!("Failed to press {chord}"))?;
+
+        debug!(%chord, times, "Keys sent via Enigo");
+        Ok(())
+    }
+
     fn copy_processed_text(&mut self, text: &str) -> Result<()> {
         if text.is_empty() {
             return Ok(());
@@ -1128,7 +1353,7 @@ impl TextInjector {
         Ok(())
     }
 
-    fn preprocess_text(&self, text: &str) -> String {
+    fn preprocess_text(&self, text: &str, window_class: Option<&str>) -> String {
         let mut steps = if tracing::level_enabled!(tracing::Level::DEBUG) {
             Some(Vec::new())
         } else {
@@ -1147,7 +1372,7 @@ impl TextInjector {
         }
         current = normalized;
 
-        let (after_overrides, override_count) = self.apply_word_overrides_with_count(&current);
+        let (after_overrides, override_count) = self.word_overrides.apply(&current, window_class);
         if let Some(ref mut logged_steps) = steps {
             logged_steps.push(PipelineStepRecord::new(
                 "word_overrides",
@@ -1177,6 +1402,25 @@ impl TextInjector {
         }
         current = after_speech;
 
+        let (after_case, case_count) = if self.case_formatting {
+            apply_case_formatting(&current)
+        } else {
+            (current.clone(), 0)
+        };
+        if let Some(ref mut logged_steps) = steps {
+            logged_steps.push(PipelineStepRecord::new(
+                "case_formatting",
+                current.clone(),
+                after_case.clone(),
+                if case_count > 0 {
+                    Some(case_count)
+                } else {
+                    None
+                },
+            ));
+        }
+        current = after_case;
+
         let cleaned_control = clean_control_artifacts(&current);
         if let Some(ref mut logged_steps) = steps {
             logged_steps.push(PipelineStepRecord::new(
@@ -1282,32 +1526,21 @@ impl TextInjector {
         final_result
     }
 
-    fn apply_word_overrides_with_count(&self, text: &str) -> (String, usize) {
-        let mut result = text.to_string();
-        let mut count = 0;
-
-        if self.word_overrides.is_empty() {
-            return (result, 0);
-        }
-
-        for (original, replacement) in &self.word_overrides {
-            // Case-insensitive word boundary replacement
-            let pattern = format!(r"\b{}\b", regex::escape(original));
-            if let Ok(re) = Regex::new(&format!("(?i){}", pattern)) {
-                let before = result.clone();
-                result = re.replace_all(&result, replacement.as_str()).to_string();
-                if before != result {
-                    count += 1;
-                }
-            }
-        }
-
-        (result, count)
+    fn apply_speech_replacements_with_count(&self, text: &str) -> (String, usize) {
+        self.speech_replacements.apply(text)
     }
+}
 
-    fn apply_speech_replacements_with_count(&self, text: &str) -> (String, usize) {
-        // Built-in speech-to-text replacements
-        apply_speech_replacements(text)
+/// The whole text at once, or one character at a time when typing with a delay
+fn typing_chunks(text: &str, delay: Duration) -> Vec<&str> {
+    if text.is_empty() {
+        Vec::new()
+    } else if delay.is_zero() {
+        vec![text]
+    } else {
+        text.char_indices()
+            .map(|(index, ch)| &text[index..index + ch.len_utf8()])
+            .collect()
     }
 }
 
@@ -1359,6 +1592,28 @@ fn normalize_line_breaks(input: &str) -> String {
     }
 }
 
+/// Surround preprocessed text with its separators: the whitespace its piece kept next to a
+/// voice command, else a space before a continuation and `trailing_whitespace` after it
+fn spaced(piece: &str, processed: String, continuation: bool, trailing_whitespace: &str) -> String {
+    let leading = &piece[..piece.len() - piece.trim_start().len()];
+    let trailing = &piece[piece.trim_end().len()..];
+
+    let mut spaced = String::with_capacity(processed.len() + 2);
+    if !leading.is_empty() {
+        spaced.push_str(leading);
+    } else if continuation && trailing_whitespace.is_empty() {
+        // The previous injection already ended with the separator otherwise
+        spaced.push(' ');
+    }
+    spaced.push_str(&processed);
+    spaced.push_str(if trailing.is_empty() {
+        trailing_whitespace
+    } else {
+        trailing
+    });
+    spaced
+}
+
 fn collapse_spaces(input: &str) -> String {
     SPACE_REGEX.replace_all(input, " ").to_string()
 }
@@ -1387,6 +1642,50 @@ fn clean_control_artifacts(input: &str) -> String {
 mod tests {
     use super::*;
 
+    fn apply_speech_replacements(text: &str) -> (String, usize) {
+        SpeechReplacements::default().apply(text)
+    }
+
+    /// What `inject` sends for `text` with the built-in commands on: pasted text as is, keys
+    /// as `<KEY>`
+    fn injected(text: &str, trailing_whitespace: &str) -> String {
+        let commands = VoiceCommands::new(&VoiceCommandsConfig {
+            enabled: true,
+            ..VoiceCommandsConfig::default()
+        })
+        .unwrap();
+        commands
+            .split(text)
+            .into_iter()
+            .map(|piece| match piece {
+                Piece::Text(text) => {
+                    spaced(&text, text.trim().to_string(), false, trailing_whitespace)
+                }
+                Piece::Command { action, .. } => format!("<{}>", action),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn undo_state_is_shared_with_rebuilt_injectors() {
+        let undo = UndoState::default();
+        let rebuilt = undo.clone();
+        undo.set(Some(12));
+        assert_eq!(rebuilt.take(), Some(12));
+        assert_eq!(undo.take(), None);
+    }
+
+    #[test]
+    fn commands_keep_the_spacing_dictated_around_them() {
+        assert_eq!(injected("foo. Press tab. bar.", ""), "foo. <Tab> bar.");
+        assert_eq!(injected("foo. Press tab. bar.", " "), "foo. <Tab> bar. ");
+        assert_eq!(injected("foo. Press enter. bar.", ""), "foo.<Return>bar.");
+        assert_eq!(
+            injected("foo. Press enter. bar.", " "),
+            "foo. <Return>bar. "
+        );
+    }
+
     #[test]
     fn removes_parenthesis_commas_and_spaces() {
         let input = "(, value, )";
@@ -1529,14 +1828,71 @@ mod tests {
     }
 
     #[test]
-    fn sanitize_word_overrides_drops_em_dash() {
-        let overrides = HashMap::from([
-            ("em dash".to_string(), "—".to_string()),
-            ("under score".to_string(), "_".to_string()),
-        ]);
-        let sanitized = sanitize_word_overrides(overrides);
-        assert!(!sanitized.contains_key("em dash"));
-        assert_eq!(sanitized.get("under score").unwrap(), "_");
+    fn case_operators_format_the_words_that_follow() {
+        let (formatted, count) = apply_case_formatting("Format snake case user account ID");
+        assert_eq!(formatted, "user_account_id");
+        assert_eq!(count, 1);
+
+        let cases = [
+            ("format camel case user account id", "userAccountId"),
+            ("format pascal case user account id", "UserAccountId"),
+            ("format kebab case user account id", "user-account-id"),
+            ("format constant case max retries", "MAX_RETRIES"),
+            ("format all caps todo", "TODO"),
+            ("format no space Hyprland ctl", "Hyprlandctl"),
+            ("Call format camel-case, get user.", "Call getUser."),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(apply_case_formatting(input).0, expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn case_operator_scope_ends_at_punctuation_end_case_or_next_operator() {
+        let (formatted, count) =
+            apply_case_formatting("set format snake case retry count, then continue");
+        assert_eq!(formatted, "set retry_count, then continue");
+        assert_eq!(count, 1);
+
+        let (formatted, _) =
+            apply_case_formatting("let format snake case user id end case equals five");
+        assert_eq!(formatted, "let user_id equals five");
+
+        let (formatted, count) =
+            apply_case_formatting("format pascal case user service format camel case get user");
+        assert_eq!(formatted, "UserService getUser");
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn case_operator_without_words_is_kept() {
+        let (formatted, count) = apply_case_formatting("I always format snake case.");
+        assert_eq!(formatted, "I always format snake case.");
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn prose_mentioning_case_styles_is_left_alone() {
+        let sentences = [
+            "there is no space left on the disk.",
+            "I prefer snake case for variables.",
+            "Headlines in all caps look loud",
+            "Use camel case, not kebab case",
+        ];
+        for sentence in sentences {
+            assert_eq!(apply_case_formatting(sentence), (sentence.to_string(), 0));
+        }
+    }
+
+    #[test]
+    fn typing_chunks_split_characters_only_with_a_delay() {
+        let text = "café 🚀\nok";
+        assert_eq!(typing_chunks(text, Duration::ZERO), [text]);
+        assert_eq!(
+            typing_chunks(text, Duration::from_millis(5)),
+            ["c", "a", "f", "é", " ", "🚀", "\n", "o", "k"]
+        );
+        assert!(typing_chunks("", Duration::ZERO).is_empty());
     }
 
     #[test]
@@ -1556,4 +1912,18 @@ Title: sample
         let class = super::HyprlandDispatcher::extract_window_class_from_response(sample).unwrap();
         assert_eq!(class, Some("foot".to_string()));
     }
+
+    #[test]
+    fn extracts_title_from_hyprland_output() {
+        let json = r#"{"address":"0x123","class":"foot","title":"vim src/main.rs"}"#;
+        let plain = "Address: 0x123456\nClass: Slack\nTitle: general | Slack\n";
+        assert_eq!(
+            super::HyprlandDispatcher::extract_window_title_from_response(json).as_deref(),
+            Some("vim src/main.rs")
+        );
+        assert_eq!(
+            super::HyprlandDispatcher::extract_window_title_from_response(plain).as_deref(),
+            Some("general | Slack")
+        );
+    }
 }
@@ -1,5 +1,8 @@
+pub mod commands;
 pub mod injector;
+pub mod overrides;
+pub mod replacements;
 pub mod shortcuts;
 
-pub use injector::TextInjector;
+pub use injector::{focused_window, ActiveWindow, TextInjector, UndoState};
 pub use shortcuts::{GlobalShortcuts, ShortcutEvent, ShortcutKind, ShortcutPhase};
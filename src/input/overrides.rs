@@ -0,0 +1,232 @@
+use crate::config::WordOverrideRule;
+use anyhow::{Context, Result};
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    regex: Regex,
+    replacement: String,
+    /// Expand `$1`-style capture references in `replacement`
+    expand: bool,
+    preserve_case: bool,
+    /// Window classes the rule is limited to; empty applies everywhere
+    window_classes: Vec<String>,
+}
+
+impl CompiledRule {
+    fn applies_to(&self, window_class: Option<&str>) -> bool {
+        self.window_classes.is_empty()
+            || window_class.is_some_and(|class| {
+                self.window_classes
+                    .iter()
+                    .any(|candidate| candidate.eq_ignore_ascii_case(class))
+            })
+    }
+
+    fn replace(&self, caps: &Captures) -> String {
+        let mut replaced = String::new();
+        if self.expand {
+            caps.expand(&self.replacement, &mut replaced);
+        } else {
+            replaced.push_str(&self.replacement);
+        }
+
+        if self.preserve_case {
+            match_case(&caps[0], &replaced)
+        } else {
+            replaced
+        }
+    }
+}
+
+/// Word overrides applied in a fixed order: the `word_overrides` map, longest phrase
+/// first, then `word_override_rules` as listed
+#[derive(Debug, Clone, Default)]
+pub struct WordOverrides {
+    rules: Vec<CompiledRule>,
+}
+
+impl WordOverrides {
+    pub fn new(
+        word_overrides: HashMap<String, String>,
+        rules: &[WordOverrideRule],
+    ) -> Result<Self> {
+        let mut literals: Vec<(String, String)> = sanitize_word_overrides(word_overrides)
+            .into_iter()
+            .collect();
+        literals.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+
+        let mut compiled = Vec::with_capacity(literals.len() + rules.len());
+        for (original, replacement) in literals {
+            compiled.push(CompiledRule {
+                regex: literal_regex(&original)?,
+                replacement,
+                expand: false,
+                preserve_case: false,
+                window_classes: Vec::new(),
+            });
+        }
+
+        for rule in rules {
+            let regex = if rule.regex {
+                Regex::new(&rule.pattern)
+                    .with_context(|| format!("Invalid word override pattern: {}", rule.pattern))?
+            } else {
+                literal_regex(&rule.pattern)?
+            };
+
+            compiled.push(CompiledRule {
+                regex,
+                replacement: rule.replacement.clone(),
+                expand: rule.regex,
+                preserve_case: rule.preserve_case,
+                window_classes: rule
+                    .window_classes
+                    .iter()
+                    .map(|class| class.trim().to_string())
+                    .filter(|class| !class.is_empty())
+                    .collect(),
+            });
+        }
+
+        Ok(Self { rules: compiled })
+    }
+
+    /// Whether any rule depends on the focused window, so callers only query it when needed
+    pub fn has_window_scoped_rules(&self) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| !rule.window_classes.is_empty())
+    }
+
+    /// Apply every rule for the focused window, returning the text and how many rules matched
+    pub fn apply(&self, text: &str, window_class: Option<&str>) -> (String, usize) {
+        let mut result = text.to_string();
+        let mut count = 0;
+
+        for rule in self
+            .rules
+            .iter()
+            .filter(|rule| rule.applies_to(window_class))
+        {
+            let replaced = rule
+                .regex
+                .replace_all(&result, |caps: &Captures| rule.replace(caps))
+                .into_owned();
+            if replaced != result {
+                result = replaced;
+                count += 1;
+            }
+        }
+
+        (result, count)
+    }
+}
+
+fn sanitize_word_overrides(mut overrides: HashMap<String, String>) -> HashMap<String, String> {
+    overrides.retain(|key, _| !key.eq_ignore_ascii_case("em dash"));
+    overrides
+}
+
+/// Case-insensitive whole word or phrase
+fn literal_regex(phrase: &str) -> Result<Regex> {
+    Regex::new(&format!(r"(?i)\b{}\b", regex::escape(phrase)))
+        .with_context(|| format!("Invalid word override: {}", phrase))
+}
+
+/// Carry the casing of `matched` over to `replacement`: all caps stays all caps, a leading
+/// capital stays a leading capital, anything else keeps the replacement as written
+fn match_case(matched: &str, replacement: &str) -> String {
+    let letters: Vec<char> = matched.chars().filter(|ch| ch.is_alphabetic()).collect();
+    if letters.len() > 1 && letters.iter().all(|ch| ch.is_uppercase()) {
+        return replacement.to_uppercase();
+    }
+
+    if letters.first().is_some_and(|ch| ch.is_uppercase()) {
+        let mut chars = replacement.chars();
+        if let Some(first) = chars.next() {
+            return first.to_uppercase().chain(chars).collect();
+        }
+    }
+
+    replacement.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, replacement: &str) -> WordOverrideRule {
+        WordOverrideRule {
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            regex: false,
+            preserve_case: false,
+            window_classes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn overlapping_map_entries_apply_longest_first() {
+        let map = HashMap::from([
+            ("hyper".to_string(), "HYPER".to_string()),
+            ("hyper land".to_string(), "hyprland".to_string()),
+        ]);
+        let overrides = WordOverrides::new(map, &[]).unwrap();
+        assert_eq!(
+            overrides.apply("hyper land is hyper", None),
+            ("hyprland is HYPER".to_string(), 2)
+        );
+    }
+
+    #[test]
+    fn regex_rules_expand_captures_and_preserve_case() {
+        let rules = [
+            WordOverrideRule {
+                regex: true,
+                ..rule(r"(?i)\bversion (\d+) point (\d+)\b", "v$1.$2")
+            },
+            WordOverrideRule {
+                preserve_case: true,
+                ..rule("git hub", "github")
+            },
+        ];
+        let overrides = WordOverrides::new(HashMap::new(), &rules).unwrap();
+
+        let (text, count) = overrides.apply("Git hub ships version 2 point 5 on GIT HUB", None);
+        assert_eq!(text, "Github ships v2.5 on GITHUB");
+        assert_eq!(count, 2);
+
+        let invalid = WordOverrideRule {
+            regex: true,
+            ..rule("(unclosed", "x")
+        };
+        assert!(WordOverrides::new(HashMap::new(), &[invalid]).is_err());
+    }
+
+    #[test]
+    fn window_scoped_rules_only_fire_in_their_apps() {
+        let rules = [WordOverrideRule {
+            window_classes: vec!["Slack".to_string()],
+            ..rule("ship it", "🚢")
+        }];
+        let overrides = WordOverrides::new(HashMap::new(), &rules).unwrap();
+        assert!(overrides.has_window_scoped_rules());
+
+        assert_eq!(overrides.apply("ship it", Some("slack")).0, "🚢");
+        assert_eq!(overrides.apply("ship it", Some("kitty")).0, "ship it");
+        assert_eq!(overrides.apply("ship it", None).0, "ship it");
+    }
+
+    #[test]
+    fn sanitize_word_overrides_drops_em_dash() {
+        let overrides = HashMap::from([
+            ("em dash".to_string(), "—".to_string()),
+            ("under score".to_string(), "_".to_string()),
+        ]);
+        let sanitized = sanitize_word_overrides(overrides);
+        assert!(!sanitized.contains_key("em dash"));
+        assert_eq!(sanitized.get("under score").unwrap(), "_");
+    }
+}
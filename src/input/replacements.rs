@@ -0,0 +1,608 @@
+use crate::config::{Config, SpeechReplacementEntry};
+use crate::paths::expand_tilde;
+use anyhow::{anyhow, Context, Result};
+use jsonc_parser::{parse_to_serde_value, ParseOptions};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy)]
+struct BuiltinReplacement {
+    phrase: &'static str,
+    replacement: &'static str,
+    adjust_preceding_punct: bool,
+}
+
+impl From<&BuiltinReplacement> for SpeechReplacementEntry {
+    fn from(entry: &BuiltinReplacement) -> Self {
+        Self {
+            phrase: entry.phrase.to_string(),
+            replacement: entry.replacement.to_string(),
+            adjust_preceding_punct: entry.adjust_preceding_punct,
+        }
+    }
+}
+
+/// The default pack, used when no other pack is selected
+static ENGLISH: &[BuiltinReplacement] = &[
+    BuiltinReplacement {
+        phrase: "period",
+        replacement: ".",
+        adjust_preceding_punct: true,
+    },
+    BuiltinReplacement {
+        phrase: "comma",
+        replacement: ",",
+        adjust_preceding_punct: true,
+    },
+    BuiltinReplacement {
+        phrase: "question mark",
+        replacement: "?",
+        adjust_preceding_punct: true,
+    },
+    BuiltinReplacement {
+        phrase: "exclamation mark",
+        replacement: "!",
+        adjust_preceding_punct: true,
+    },
+    BuiltinReplacement {
+        phrase: "exclamation point",
+        replacement: "!",
+        adjust_preceding_punct: true,
+    },
+    BuiltinReplacement {
+        phrase: "colon",
+        replacement: ":",
+        adjust_preceding_punct: true,
+    },
+    BuiltinReplacement {
+        phrase: "semicolon",
+        replacement: ";",
+        adjust_preceding_punct: true,
+    },
+    BuiltinReplacement {
+        phrase: "new line",
+        replacement: "\n",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "tab",
+        replacement: "\t",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "dash",
+        replacement: "-",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "dash dash",
+        replacement: "--",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "hyphen",
+        replacement: "-",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "underscore",
+        replacement: "_",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "open paren",
+        replacement: "(",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "open parenthesis",
+        replacement: "(",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "open parentheses",
+        replacement: "(",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "close paren",
+        replacement: ")",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "close parenthesis",
+        replacement: ")",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "close parentheses",
+        replacement: ")",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "open bracket",
+        replacement: "[",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "close bracket",
+        replacement: "]",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "open brace",
+        replacement: "{",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "close brace",
+        replacement: "}",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "at symbol",
+        replacement: "@",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "hash",
+        replacement: "#",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "dollar sign",
+        replacement: "$",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "percent",
+        replacement: "%",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "caret",
+        replacement: "^",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "ampersand",
+        replacement: "&",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "asterisk",
+        replacement: "*",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "plus",
+        replacement: "+",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "equals",
+        replacement: "=",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "less than",
+        replacement: "<",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "greater than",
+        replacement: ">",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "slash",
+        replacement: "/",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "backslash",
+        replacement: "\\",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "pipe",
+        replacement: "|",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "tilde",
+        replacement: "~",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "grave",
+        replacement: "`",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "quote",
+        replacement: "\"",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "double quote",
+        replacement: "\"",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "apostrophe",
+        replacement: "'",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "single quote",
+        replacement: "'",
+        adjust_preceding_punct: false,
+    },
+];
+
+static GERMAN: &[BuiltinReplacement] = &[
+    BuiltinReplacement {
+        phrase: "Punkt",
+        replacement: ".",
+        adjust_preceding_punct: true,
+    },
+    BuiltinReplacement {
+        phrase: "Komma",
+        replacement: ",",
+        adjust_preceding_punct: true,
+    },
+    BuiltinReplacement {
+        phrase: "Fragezeichen",
+        replacement: "?",
+        adjust_preceding_punct: true,
+    },
+    BuiltinReplacement {
+        phrase: "Ausrufezeichen",
+        replacement: "!",
+        adjust_preceding_punct: true,
+    },
+    BuiltinReplacement {
+        phrase: "Doppelpunkt",
+        replacement: ":",
+        adjust_preceding_punct: true,
+    },
+    BuiltinReplacement {
+        phrase: "Semikolon",
+        replacement: ";",
+        adjust_preceding_punct: true,
+    },
+    BuiltinReplacement {
+        phrase: "neue Zeile",
+        replacement: "\n",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "Bindestrich",
+        replacement: "-",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "Unterstrich",
+        replacement: "_",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "Klammer auf",
+        replacement: "(",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "Klammer zu",
+        replacement: ")",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "eckige Klammer auf",
+        replacement: "[",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "eckige Klammer zu",
+        replacement: "]",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "geschweifte Klammer auf",
+        replacement: "{",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "geschweifte Klammer zu",
+        replacement: "}",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "Schrägstrich",
+        replacement: "/",
+        adjust_preceding_punct: false,
+    },
+    BuiltinReplacement {
+        phrase: "Anführungszeichen",
+        replacement: "\"",
+        adjust_preceding_punct: false,
+    },
+];
+
+fn builtin_pack(name: &str) -> Option<&'static [BuiltinReplacement]> {
+    match name {
+        "en" => Some(ENGLISH),
+        "de" => Some(GERMAN),
+        _ => None,
+    }
+}
+
+/// The spoken punctuation table the injector applies, assembled from packs and config
+/// entries. Phrases match case-insensitively on word boundaries, longest first.
+#[derive(Debug, Clone)]
+pub struct SpeechReplacements {
+    regex: Option<Regex>,
+    lookup: HashMap<String, SpeechReplacementEntry>,
+}
+
+impl Default for SpeechReplacements {
+    /// The built-in English pack
+    fn default() -> Self {
+        Self::from_entries(ENGLISH.iter().map(SpeechReplacementEntry::from))
+    }
+}
+
+impl SpeechReplacements {
+    /// Load the packs selected by `speech_replacements` (pack files from `packs_dir`) and
+    /// add its entries on top
+    pub fn load(config: &Config, packs_dir: &Path) -> Result<Self> {
+        let settings = &config.speech_replacements;
+        let packs = match &settings.packs {
+            Some(packs) => packs.clone(),
+            None => vec![default_pack(config, packs_dir)],
+        };
+
+        let mut entries = Vec::new();
+        for pack in &packs {
+            entries.extend(
+                load_pack(pack.trim(), packs_dir)
+                    .with_context(|| format!("Failed to load speech replacement pack {pack}"))?,
+            );
+        }
+        entries.extend(settings.entries.iter().cloned());
+
+        Ok(Self::from_entries(entries))
+    }
+
+    /// Later entries replace earlier ones with the same phrase
+    pub fn from_entries(entries: impl IntoIterator<Item = SpeechReplacementEntry>) -> Self {
+        let mut lookup = HashMap::new();
+        for entry in entries {
+            let key = entry.phrase.trim().to_lowercase();
+            if !key.is_empty() {
+                lookup.insert(key, entry);
+            }
+        }
+
+        let mut phrases: Vec<&String> = lookup.keys().collect();
+        phrases.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        let regex = (!phrases.is_empty()).then(|| {
+            let alternates = phrases
+                .into_iter()
+                .map(|phrase| regex::escape(phrase))
+                .collect::<Vec<_>>()
+                .join("|");
+            let pattern = format!(r"(?i)\b(?P<command>{})\b[.!?,;:]*", alternates);
+            Regex::new(&pattern).expect("valid speech replacement regex")
+        });
+
+        Self { regex, lookup }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lookup.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lookup.is_empty()
+    }
+
+    pub fn apply(&self, text: &str) -> (String, usize) {
+        let Some(regex) = &self.regex else {
+            return (text.to_string(), 0);
+        };
+
+        let mut result = String::with_capacity(text.len());
+        let mut last_end = 0;
+        let mut count = 0;
+
+        for caps in regex.captures_iter(text) {
+            let matched = caps.get(0).expect("regex match");
+            result.push_str(&text[last_end..matched.start()]);
+
+            if let Some(command) = caps.name("command") {
+                let key = command.as_str().to_lowercase();
+                if let Some(entry) = self.lookup.get(key.as_str()) {
+                    apply_entry(&mut result, entry);
+                    count += 1;
+                }
+            }
+
+            last_end = matched.end();
+        }
+
+        result.push_str(&text[last_end..]);
+        (result, count)
+    }
+}
+
+/// The pack for the transcription language when there is one, else English. Translated
+/// output is English whatever was spoken.
+fn default_pack(config: &Config, packs_dir: &Path) -> String {
+    let language = if config.transcription.translate {
+        None
+    } else {
+        config.transcription.language_code()
+    };
+
+    match language {
+        Some(code) if builtin_pack(&code).is_some() || pack_file(&code, packs_dir).is_some() => {
+            code
+        }
+        _ => "en".to_string(),
+    }
+}
+
+/// `<name>.jsonc` or `<name>.json` in the packs directory, or `name` itself when it is a path
+fn pack_file(name: &str, packs_dir: &Path) -> Option<PathBuf> {
+    if name.contains('/') {
+        let path = expand_tilde(name);
+        return path.is_file().then_some(path);
+    }
+
+    ["jsonc", "json"]
+        .into_iter()
+        .map(|extension| packs_dir.join(format!("{name}.{extension}")))
+        .find(|path| path.is_file())
+}
+
+/// A pack file is a JSONC array of `{ "phrase", "replacement", "adjust_preceding_punct" }`
+fn load_pack(name: &str, packs_dir: &Path) -> Result<Vec<SpeechReplacementEntry>> {
+    if let Some(path) = pack_file(name, packs_dir) {
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let value = parse_to_serde_value(&content, &ParseOptions::default())
+            .with_context(|| format!("Failed to parse {} as JSONC", path.display()))?
+            .ok_or_else(|| anyhow!("{} did not contain a JSON value", path.display()))?;
+        return serde_json::from_value(value)
+            .with_context(|| format!("Failed to deserialize {}", path.display()));
+    }
+
+    builtin_pack(name)
+        .map(|pack| pack.iter().map(SpeechReplacementEntry::from).collect())
+        .ok_or_else(|| {
+            anyhow!(
+                "no built-in pack of that name and no such file in {}",
+                packs_dir.display()
+            )
+        })
+}
+
+fn apply_entry(buffer: &mut String, entry: &SpeechReplacementEntry) {
+    if entry.adjust_preceding_punct {
+        let mut trailing_ws: Vec<char> = Vec::new();
+
+        loop {
+            if buffer.ends_with(' ') {
+                buffer.pop();
+                trailing_ws.push(' ');
+            } else if buffer.ends_with('\t') {
+                buffer.pop();
+                trailing_ws.push('\t');
+            } else {
+                break;
+            }
+        }
+
+        loop {
+            let Some(ch) = buffer.chars().last() else {
+                break;
+            };
+            if matches!(ch, '.' | ',' | '!' | '?' | ';' | ':') {
+                buffer.pop();
+            } else {
+                break;
+            }
+        }
+
+        buffer.push_str(&entry.replacement);
+        for ch in trailing_ws.into_iter().rev() {
+            buffer.push(ch);
+        }
+    } else {
+        buffer.push_str(&entry.replacement);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(
+        phrase: &str,
+        replacement: &str,
+        adjust_preceding_punct: bool,
+    ) -> SpeechReplacementEntry {
+        SpeechReplacementEntry {
+            phrase: phrase.to_string(),
+            replacement: replacement.to_string(),
+            adjust_preceding_punct,
+        }
+    }
+
+    /// Applied text with the whitespace the injector's later cleanup collapses anyway
+    fn applied(replacements: &SpeechReplacements, text: &str) -> String {
+        let (output, _) = replacements.apply(text);
+        output.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn packs_follow_the_transcription_language() {
+        let packs_dir = Path::new("/nonexistent");
+        let mut config = Config::default();
+        config.transcription.language = "de".to_string();
+        let replacements = SpeechReplacements::load(&config, packs_dir).unwrap();
+        assert_eq!(applied(&replacements, "fertig Punkt"), "fertig.");
+
+        config.transcription.language = "fr".to_string();
+        let replacements = SpeechReplacements::load(&config, packs_dir).unwrap();
+        assert_eq!(applied(&replacements, "fini period"), "fini.");
+    }
+
+    #[test]
+    fn config_entries_extend_and_override_packs() {
+        let mut config = Config::default();
+        config.speech_replacements.packs = Some(vec!["en".to_string(), "de".to_string()]);
+        config.speech_replacements.entries =
+            vec![entry("ellipsis", "…", true), entry("dash", "–", false)];
+        let replacements = SpeechReplacements::load(&config, Path::new("/nonexistent")).unwrap();
+
+        assert_eq!(
+            applied(&replacements, "wait, ellipsis Komma a dash b"),
+            "wait…, a – b"
+        );
+        assert_eq!(replacements.apply("wait, ellipsis Komma a dash b").1, 3);
+    }
+
+    #[test]
+    fn pack_files_are_loaded_from_the_packs_dir() {
+        let packs_dir =
+            std::env::temp_dir().join(format!("hyprwhspr-packs-{}", std::process::id()));
+        fs::create_dir_all(&packs_dir).unwrap();
+        fs::write(
+            packs_dir.join("arrows.jsonc"),
+            r#"[
+                // Comments are fine
+                {"phrase": "arrow", "replacement": "->"},
+                {"phrase": "fat arrow", "replacement": "=>"},
+            ]"#,
+        )
+        .unwrap();
+
+        let mut config = Config::default();
+        config.speech_replacements.packs = Some(vec!["arrows".to_string()]);
+        let replacements = SpeechReplacements::load(&config, &packs_dir).unwrap();
+        assert_eq!(replacements.len(), 2);
+        assert_eq!(
+            applied(&replacements, "a arrow b fat arrow c"),
+            "a -> b => c"
+        );
+
+        config.speech_replacements.packs = Some(vec!["missing".to_string()]);
+        assert!(SpeechReplacements::load(&config, &packs_dir).is_err());
+
+        fs::remove_dir_all(&packs_dir).unwrap();
+    }
+}
@@ -14,7 +14,9 @@ use tracing::{debug, error, info, warn};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ShortcutKind {
+    /// Reports both the press and the release of the combination
     Hold,
+    /// Reports presses only, debounced
     Press,
 }
 
@@ -27,6 +29,8 @@ pub enum ShortcutPhase {
 #[derive(Debug, Clone)]
 pub struct ShortcutEvent {
     pub triggered_at: Instant,
+    /// Index of the binding whose listener fired
+    pub binding: usize,
     pub kind: ShortcutKind,
     pub phase: ShortcutPhase,
 }
@@ -36,10 +40,11 @@ pub struct GlobalShortcuts {
     target_keys: HashSet<Key>,
     shortcut_name: String,
     kind: ShortcutKind,
+    binding: usize,
 }
 
 impl GlobalShortcuts {
-    pub fn new(shortcut: &str, kind: ShortcutKind) -> Result<Self> {
+    pub fn new(shortcut: &str, kind: ShortcutKind, binding: usize) -> Result<Self> {
         let target_keys = Self::parse_shortcut(shortcut)?;
         let devices = Self::find_keyboard_devices()?;
 
@@ -65,6 +70,7 @@ impl GlobalShortcuts {
             target_keys,
             shortcut_name: shortcut.to_string(),
             kind,
+            binding,
         })
     }
 
@@ -151,6 +157,7 @@ impl GlobalShortcuts {
 
                                                     if let Err(e) = tx.try_send(ShortcutEvent {
                                                         triggered_at: now,
+                                                        binding: self.binding,
                                                         kind: self.kind,
                                                         phase: ShortcutPhase::Start,
                                                     }) {
@@ -180,6 +187,7 @@ impl GlobalShortcuts {
                                                 if matches!(self.kind, ShortcutKind::Hold) {
                                                     if let Err(e) = tx.try_send(ShortcutEvent {
                                                         triggered_at: Instant::now(),
+                                                        binding: self.binding,
                                                         kind: self.kind,
                                                         phase: ShortcutPhase::End,
                                                     }) {
@@ -4,8 +4,11 @@ pub mod audio;
 pub mod benchmark;
 pub mod cli;
 pub mod config;
+pub mod control;
+pub mod events;
 pub mod input;
 pub mod install;
+pub mod llm;
 pub mod logging;
 pub mod paths;
 pub mod status;
@@ -0,0 +1,327 @@
+use crate::config::LlmPostProcessConfig;
+use crate::logging::{record_text_pipeline, PipelineStepRecord, TextPipelineRecord};
+use crate::paths::expand_tilde;
+use anyhow::{anyhow, Context, Result};
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Sends transcripts through an OpenAI-compatible `/chat/completions` endpoint (OpenAI,
+/// a local llama.cpp server, ...) with a system prompt, e.g. to fix grammar or rewrite
+/// the dictation as a commit message.
+pub struct LlmPostProcessor {
+    client: Client,
+    endpoint: Url,
+    api_key: Option<String>,
+    model: String,
+    temperature: f32,
+    system_prompt: String,
+    /// Selected profile, for logs
+    profile: Option<String>,
+    timeout: Duration,
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    temperature: f32,
+    messages: [ChatMessage<'a>; 2],
+}
+
+#[derive(Serialize)]
+struct ChatMessage<'a> {
+    role: &'static str,
+    content: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    #[serde(default)]
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatReply,
+}
+
+#[derive(Deserialize)]
+struct ChatReply {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+impl LlmPostProcessor {
+    /// `None` when post-processing is turned off
+    pub fn new(config: &LlmPostProcessConfig) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        let base_url = config.base_url.trim().trim_end_matches('/');
+        let endpoint = Url::parse(&format!("{base_url}/chat/completions"))
+            .with_context(|| format!("Invalid llm_postprocess base URL: {}", config.base_url))?;
+        let timeout = Duration::from_millis(config.timeout_ms.max(1));
+
+        let client = Client::builder()
+            .user_agent("hyprwhspr-rs (llm-postprocess)")
+            .connect_timeout(timeout.min(Duration::from_secs(10)))
+            .timeout(timeout)
+            .pool_idle_timeout(Duration::from_secs(30))
+            .build()
+            .context("Failed to build LLM post-processing HTTP client")?;
+
+        let processor = Self {
+            client,
+            endpoint,
+            api_key: resolve_api_key(config)?,
+            model: config.model.clone(),
+            temperature: config.temperature,
+            system_prompt: config.system_prompt()?.to_string(),
+            profile: config
+                .profile
+                .as_deref()
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(String::from),
+            timeout,
+        };
+
+        info!(
+            "✨ LLM post-processing enabled (endpoint: {}, model: {}, profile: {}, timeout: {:?})",
+            processor.endpoint,
+            processor.model,
+            processor.profile.as_deref().unwrap_or("default"),
+            processor.timeout
+        );
+
+        Ok(Some(processor))
+    }
+
+    /// The rewritten transcript, or `text` unchanged when the request fails, times out or
+    /// comes back empty. Either way the outcome is recorded in the text pipeline log.
+    pub async fn process(&self, text: &str) -> String {
+        let start = Instant::now();
+        let outcome = match tokio::time::timeout(self.timeout, self.complete(text)).await {
+            Ok(Ok(rewritten)) if !rewritten.is_empty() => Ok(rewritten),
+            Ok(Ok(_)) => Err("empty reply".to_string()),
+            Ok(Err(err)) => Err(format!("{:#}", err)),
+            Err(_) => Err(format!("timed out after {:?}", self.timeout)),
+        };
+
+        let label = self.profile.as_deref().unwrap_or("default");
+        let (output, step) = match outcome {
+            Ok(rewritten) => {
+                info!(
+                    "✨ LLM post-processing ({}) took {:.2}s",
+                    label,
+                    start.elapsed().as_secs_f32()
+                );
+                (rewritten, format!("llm_postprocess ({})", label))
+            }
+            Err(reason) => {
+                warn!(
+                    "LLM post-processing failed, keeping the transcript as is: {}",
+                    reason
+                );
+                (
+                    text.to_string(),
+                    format!("llm_postprocess ({}, skipped: {})", label, reason),
+                )
+            }
+        };
+
+        record_text_pipeline(TextPipelineRecord::new(
+            text.to_string(),
+            output.clone(),
+            vec![PipelineStepRecord::new(
+                step,
+                text.to_string(),
+                output.clone(),
+                None,
+            )],
+        ));
+
+        output
+    }
+
+    async fn complete(&self, text: &str) -> Result<String> {
+        let body = ChatRequest {
+            model: &self.model,
+            temperature: self.temperature,
+            messages: [
+                ChatMessage {
+                    role: "system",
+                    content: &self.system_prompt,
+                },
+                ChatMessage {
+                    role: "user",
+                    content: text,
+                },
+            ],
+        };
+
+        let mut request = self.client.post(self.endpoint.clone()).json(&body);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .context("Failed to send chat completion request")?;
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .context("Failed to read chat completion response")?;
+
+        if !status.is_success() {
+            return Err(anyhow!("Chat completion failed with {}: {}", status, body));
+        }
+
+        let parsed: ChatResponse = serde_json::from_str(&body)
+            .context("Failed to deserialize chat completion response")?;
+        Ok(parsed
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.message.content)
+            .unwrap_or_default()
+            .trim()
+            .to_string())
+    }
+}
+
+/// `api_key_env` wins over `api_key_file`; neither means an unauthenticated server.
+fn resolve_api_key(config: &LlmPostProcessConfig) -> Result<Option<String>> {
+    let key = if let Some(var) = &config.api_key_env {
+        env::var(var).with_context(|| format!("{var} environment variable is not set"))?
+    } else if let Some(path) = &config.api_key_file {
+        let path = expand_tilde(path);
+        fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read API key file {}", path.display()))?
+    } else {
+        return Ok(None);
+    };
+
+    let key = key.trim();
+    if key.is_empty() {
+        anyhow::bail!("The configured API key for LLM post-processing is empty");
+    }
+    Ok(Some(key.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::task::JoinHandle;
+
+    /// Answers one connection with `body` after `delay`, resolving to the raw request
+    async fn stub_server(body: &'static str, delay: Duration) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut raw = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let read = stream.read(&mut buf).await.unwrap();
+                raw.extend_from_slice(&buf[..read]);
+                let text = String::from_utf8_lossy(&raw);
+                let complete = text.find("\r\n\r\n").is_some_and(|header_end| {
+                    let length = text[..header_end]
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().ok())?
+                        })
+                        .unwrap_or(0);
+                    raw.len() >= header_end + 4 + length
+                });
+                if complete || read == 0 {
+                    break;
+                }
+            }
+
+            tokio::time::sleep(delay).await;
+            let reply = format!(
+                "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = stream.write_all(reply.as_bytes()).await;
+            String::from_utf8_lossy(&raw).into_owned()
+        });
+
+        (base_url, handle)
+    }
+
+    fn processor(base_url: String, timeout_ms: u64) -> LlmPostProcessor {
+        let config = LlmPostProcessConfig {
+            enabled: true,
+            base_url,
+            timeout_ms,
+            profile: Some("commit".to_string()),
+            profiles: [(
+                "commit".to_string(),
+                "Rewrite as a commit message".to_string(),
+            )]
+            .into_iter()
+            .collect(),
+            ..LlmPostProcessConfig::default()
+        };
+        LlmPostProcessor::new(&config).unwrap().unwrap()
+    }
+
+    #[tokio::test]
+    async fn sends_profile_prompt_and_returns_reply() {
+        let (base_url, server) = stub_server(
+            r#"{"choices":[{"message":{"role":"assistant","content":" Fix parser crash \n"}}]}"#,
+            Duration::ZERO,
+        )
+        .await;
+
+        let output = processor(base_url, 2000)
+            .process("um fix the parser crash")
+            .await;
+        assert_eq!(output, "Fix parser crash");
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /v1/chat/completions"));
+        assert!(request.contains(r#""content":"Rewrite as a commit message""#));
+        assert!(request.contains(r#""content":"um fix the parser crash""#));
+    }
+
+    #[tokio::test]
+    async fn keeps_transcript_when_the_model_is_too_slow() {
+        let (base_url, _server) = stub_server(
+            r#"{"choices":[{"message":{"content":"late"}}]}"#,
+            Duration::from_secs(2),
+        )
+        .await;
+
+        let output = processor(base_url, 100).process("keep me").await;
+        assert_eq!(output, "keep me");
+    }
+
+    #[test]
+    fn disabled_or_unknown_profile() {
+        assert!(LlmPostProcessor::new(&LlmPostProcessConfig::default())
+            .unwrap()
+            .is_none());
+
+        let config = LlmPostProcessConfig {
+            enabled: true,
+            profile: Some("missing".to_string()),
+            ..LlmPostProcessConfig::default()
+        };
+        assert!(LlmPostProcessor::new(&config).is_err());
+    }
+}
@@ -3,7 +3,7 @@ use clap::Parser;
 use hyprwhspr_rs::{
     cli::{Cli, Command},
     config::TranscriptionProvider,
-    install,
+    control, install,
     logging::TextPipelineFormatter,
     ConfigManager, HyprwhsprApp,
 };
@@ -20,6 +20,11 @@ async fn main() -> Result<()> {
         return install::run_install(&args);
     }
 
+    // Control commands talk to an already running daemon and exit
+    if let Some(Command::Ctl(args)) = cli.command {
+        return control::run_ctl(&args).await;
+    }
+
     // Initialize logging
     tracing_subscriber::registry()
         .with(
@@ -2,6 +2,12 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, RwLock,
+};
+
+use crate::events::{DaemonEvent, DaemonState};
 
 /// XDG-compliant paths for hyprwhspr-rs data
 pub mod paths {
@@ -28,6 +34,17 @@ pub mod paths {
     pub fn history_file() -> PathBuf {
         data_dir().join("transcriptions.json")
     }
+
+    /// $XDG_RUNTIME_DIR - per-session sockets (falls back to the cache dir)
+    pub fn runtime_dir() -> PathBuf {
+        directories::BaseDirs::new()
+            .and_then(|d| d.runtime_dir().map(|p| p.to_path_buf()))
+            .unwrap_or_else(cache_dir)
+    }
+
+    pub fn control_socket() -> PathBuf {
+        runtime_dir().join("hyprwhspr-rs.sock")
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -37,6 +54,7 @@ pub enum WaybarState {
     Active,
     Processing,
     Error,
+    Cancelled,
 }
 
 impl WaybarState {
@@ -46,6 +64,7 @@ impl WaybarState {
             Self::Active => "󰍬",    // mic on icon
             Self::Processing => "󰍬",
             Self::Error => "󰍭",     // mic off with error styling
+            Self::Cancelled => "󰍭",
         }
     }
 
@@ -55,6 +74,7 @@ impl WaybarState {
             Self::Active => "active",
             Self::Processing => "processing",
             Self::Error => "error",
+            Self::Cancelled => "cancelled",
         }
     }
 }
@@ -71,14 +91,22 @@ struct WaybarStatus {
 pub struct TranscriptionEntry {
     pub text: String,
     pub timestamp: String,
+    /// Backend that produced the text (absent in entries written by older versions)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
 }
 
 /// Writes recording status for Waybar to read (JSON format)
 /// Uses atomic writes (temp file + rename) for inotify reliability
+#[derive(Clone)]
 pub struct StatusWriter {
     status_file: PathBuf,
     history_file: PathBuf,
     max_history: usize,
+    /// Shared across clones so background jobs never hide a live recording
+    recording: Arc<AtomicBool>,
+    /// Selected profile, shown in every tooltip
+    profile: Arc<RwLock<Option<String>>>,
 }
 
 impl StatusWriter {
@@ -93,14 +121,20 @@ impl StatusWriter {
             status_file,
             history_file,
             max_history: 20,
+            recording: Arc::new(AtomicBool::new(false)),
+            profile: Arc::new(RwLock::new(None)),
         })
     }
 
     /// Update Waybar status with state and tooltip using atomic write
     pub fn set_state(&self, state: WaybarState, tooltip: &str) -> Result<()> {
+        let tooltip = match self.profile.read().ok().and_then(|profile| profile.clone()) {
+            Some(profile) => format!("{}\nProfile: {}", tooltip, profile),
+            None => tooltip.to_string(),
+        };
         let status = WaybarStatus {
             text: state.icon().to_string(),
-            tooltip: tooltip.to_string(),
+            tooltip: tooltip.clone(),
             class: state.class().to_string(),
             alt: state.class().to_string(),
         };
@@ -117,6 +151,52 @@ impl StatusWriter {
         Ok(())
     }
 
+    /// Reflect a daemon event in the Waybar status and transcription history
+    pub fn apply(&self, event: &DaemonEvent) -> Result<()> {
+        match event {
+            DaemonEvent::RecordingStarted => {
+                self.recording.store(true, Ordering::Relaxed);
+                self.set_recording(true)
+            }
+            DaemonEvent::RecordingLimitApproaching { remaining_secs } => self.set_state(
+                WaybarState::Active,
+                &format!("Recording... stops in {}s", remaining_secs),
+            ),
+            DaemonEvent::RecordingStopped { .. } => {
+                self.recording.store(false, Ordering::Relaxed);
+                Ok(())
+            }
+            DaemonEvent::Cancelled {
+                stage: DaemonState::Recording,
+            } => {
+                self.recording.store(false, Ordering::Relaxed);
+                self.set_state(WaybarState::Cancelled, "Cancelled")
+            }
+            DaemonEvent::Transcription { text, provider } => {
+                self.save_transcription(text, provider)
+            }
+            // The state event that follows renders the new tooltip
+            DaemonEvent::ProfileChanged { profile } => {
+                self.set_profile(profile.as_deref());
+                Ok(())
+            }
+            // Queued transcriptions keep running while recording; the bar shows the recording
+            _ if self.recording.load(Ordering::Relaxed) => Ok(()),
+            DaemonEvent::Ready => self.set_state(WaybarState::Inactive, "Ready"),
+            DaemonEvent::Processing => self.set_processing(),
+            DaemonEvent::Error { message } => self.set_error(message),
+            DaemonEvent::Cancelled { .. } => self.set_state(WaybarState::Cancelled, "Cancelled"),
+            _ => Ok(()),
+        }
+    }
+
+    /// Profile to mention in tooltips from the next state change on
+    pub fn set_profile(&self, profile: Option<&str>) {
+        if let Ok(mut current) = self.profile.write() {
+            *current = profile.map(str::to_string);
+        }
+    }
+
     /// Legacy method for backward compatibility
     pub fn set_recording(&self, recording: bool) -> Result<()> {
         if recording {
@@ -146,7 +226,7 @@ impl StatusWriter {
     }
 
     /// Save transcription to history (for Walker/Elephant integration)
-    pub fn save_transcription(&self, text: &str) -> Result<()> {
+    pub fn save_transcription(&self, text: &str, provider: &str) -> Result<()> {
         let mut entries: Vec<TranscriptionEntry> = fs::read_to_string(&self.history_file)
             .ok()
             .and_then(|s| serde_json::from_str(&s).ok())
@@ -165,6 +245,7 @@ impl StatusWriter {
             TranscriptionEntry {
                 text: text.to_string(),
                 timestamp,
+                provider: Some(provider.to_string()),
             },
         );
 
@@ -178,6 +259,14 @@ impl StatusWriter {
         Ok(())
     }
 
+    /// Most recent entry of the transcription history
+    pub fn last_transcription(&self) -> Option<String> {
+        let entries: Vec<TranscriptionEntry> = fs::read_to_string(&self.history_file)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())?;
+        entries.into_iter().next().map(|entry| entry.text)
+    }
+
     /// Clean up status file on shutdown
     pub fn cleanup(&self) -> Result<()> {
         // Write final inactive state
@@ -1,137 +1,106 @@
-use anyhow::{Context, Result};
+use crate::config::UploadFormat;
+use crate::transcription::flac;
 use bytes::Bytes;
-use tokio::io::{AsyncReadExt, AsyncWriteExt, BufWriter};
-use tokio::process::Command;
-use tokio::try_join;
 use tracing::debug;
 
+const SAMPLE_RATE: u32 = 16000;
+
 pub struct EncodedAudio {
     pub data: Bytes,
     pub content_type: &'static str,
 }
 
-/// Encodes raw PCM audio (mono, 16 kHz, f32 samples) into FLAC using ffmpeg.
+impl EncodedAudio {
+    /// File name for multipart uploads, matching the content type
+    pub fn file_name(&self) -> &'static str {
+        match self.content_type {
+            "audio/wav" => "audio.wav",
+            _ => "audio.flac",
+        }
+    }
+}
+
+/// Encodes raw PCM audio (mono, 16 kHz, f32 samples) for upload in the configured format.
+pub fn encode_audio(audio: &[f32], format: UploadFormat) -> EncodedAudio {
+    match format {
+        UploadFormat::Flac => encode_to_flac(audio),
+        UploadFormat::Wav => encode_to_wav(audio),
+    }
+}
+
+/// Encodes raw PCM audio (mono, 16 kHz, f32 samples) into FLAC in-process.
 ///
 /// FLAC offers lossless compression with ~40-60% smaller payloads compared to WAV
 /// for 16 kHz speech, while preserving Whisper-grade accuracy. Alternative lossy
 /// codecs (e.g. Opus) offer smaller payloads but cause hallucinations in tests with
 /// both Groq Whisper and Gemini 2.5 Pro Flash, so we stick with FLAC here.
-pub async fn encode_to_flac(audio: &[f32]) -> Result<EncodedAudio> {
+pub fn encode_to_flac(audio: &[f32]) -> EncodedAudio {
     if audio.is_empty() {
-        return Ok(EncodedAudio {
+        return EncodedAudio {
             data: Bytes::new(),
             content_type: "audio/flac",
-        });
+        };
     }
 
-    let mut child = Command::new("ffmpeg")
-        .arg("-hide_banner")
-        .arg("-loglevel")
-        .arg("error")
-        .arg("-f")
-        .arg("f32le")
-        .arg("-ar")
-        .arg("16000")
-        .arg("-ac")
-        .arg("1")
-        .arg("-i")
-        .arg("pipe:0")
-        .arg("-compression_level")
-        .arg("12")
-        .arg("-f")
-        .arg("flac")
-        .arg("pipe:1")
-        .stdin(std::process::Stdio::piped())
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
-        .spawn()
-        .context("Failed to spawn ffmpeg for FLAC encoding. Ensure ffmpeg is installed")?;
-
-    let mut stdin = child.stdin.take().context("Failed to open ffmpeg stdin")?;
-    let mut stdout = child
-        .stdout
-        .take()
-        .context("Failed to open ffmpeg stdout")?;
-    let mut stderr = child
-        .stderr
-        .take()
-        .context("Failed to open ffmpeg stderr")?;
-
-    let audio_chunks = audio;
-
-    let write_future = async move {
-        let mut writer = BufWriter::new(&mut stdin);
-        const CHUNK_SIZE: usize = 4096;
-        let mut buffer = vec![0u8; CHUNK_SIZE * std::mem::size_of::<f32>()];
-
-        for chunk in audio_chunks.chunks(CHUNK_SIZE) {
-            let required = chunk.len() * std::mem::size_of::<f32>();
-            if buffer.len() < required {
-                buffer.resize(required, 0);
-            }
-
-            for (idx, sample) in chunk.iter().enumerate() {
-                let bytes = sample.to_le_bytes();
-                let offset = idx * 4;
-                buffer[offset..offset + 4].copy_from_slice(&bytes);
-            }
-
-            writer
-                .write_all(&buffer[..required])
-                .await
-                .context("Failed to stream PCM audio into ffmpeg")?;
-        }
-
-        writer
-            .flush()
-            .await
-            .context("Failed to flush PCM audio into ffmpeg")?;
-        stdin
-            .shutdown()
-            .await
-            .context("Failed to close ffmpeg stdin")?;
-        Ok::<(), anyhow::Error>(())
-    };
-
-    let read_future = async move {
-        let mut encoded = Vec::new();
-        stdout
-            .read_to_end(&mut encoded)
-            .await
-            .context("Failed to read FLAC output from ffmpeg")?;
-        Ok::<Bytes, anyhow::Error>(Bytes::from(encoded))
-    };
-
-    let stderr_future = async move {
-        let mut buf = Vec::new();
-        stderr
-            .read_to_end(&mut buf)
-            .await
-            .context("Failed to read ffmpeg stderr")?;
-        Ok::<Bytes, anyhow::Error>(Bytes::from(buf))
-    };
-
-    let (_, encoded, stderr_bytes) = try_join!(write_future, read_future, stderr_future)?;
-
-    let status = child.wait().await.context("Failed to wait for ffmpeg")?;
-
-    if !status.success() {
-        let stderr_text = String::from_utf8_lossy(&stderr_bytes);
-        return Err(anyhow::anyhow!(
-            "ffmpeg exited with status {:?}: {}",
-            status.code(),
-            stderr_text
-        ));
-    }
+    let encoded = flac::encode(&to_pcm16(audio), SAMPLE_RATE);
 
     debug!(
         "Encoded PCM into FLAC ({} bytes -> {} bytes)",
-        audio.len() * std::mem::size_of::<f32>(),
+        std::mem::size_of_val(audio),
         encoded.len()
     );
 
-    Ok(EncodedAudio {
-        data: encoded,
+    EncodedAudio {
+        data: Bytes::from(encoded),
         content_type: "audio/flac",
-    })
+    }
+}
+
+/// Encodes raw PCM audio (mono, 16 kHz, f32 samples) as a 16-bit PCM WAV file in memory.
+pub fn encode_to_wav(audio: &[f32]) -> EncodedAudio {
+    let samples = to_pcm16(audio);
+
+    let channels: u16 = 1;
+    let bits_per_sample: u16 = 16;
+    let byte_rate = SAMPLE_RATE * channels as u32 * bits_per_sample as u32 / 8;
+    let block_align = channels * bits_per_sample / 8;
+    let data_size = (samples.len() * 2) as u32;
+
+    let mut wav = Vec::with_capacity(44 + data_size as usize);
+
+    // RIFF header
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+
+    // fmt chunk
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes()); // Chunk size
+    wav.extend_from_slice(&1u16.to_le_bytes()); // Audio format (PCM)
+    wav.extend_from_slice(&channels.to_le_bytes());
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&byte_rate.to_le_bytes());
+    wav.extend_from_slice(&block_align.to_le_bytes());
+    wav.extend_from_slice(&bits_per_sample.to_le_bytes());
+
+    // data chunk
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    EncodedAudio {
+        data: Bytes::from(wav),
+        content_type: "audio/wav",
+    }
+}
+
+/// Convert f32 samples to i16
+fn to_pcm16(audio: &[f32]) -> Vec<i16> {
+    audio
+        .iter()
+        .map(|&sample| (sample * 32767.0).clamp(-32768.0, 32767.0) as i16)
+        .collect()
 }
@@ -0,0 +1,408 @@
+use super::{HallucinationGuard, TranscriptionBackend, TranscriptionResult, TranscriptionTask};
+use crate::config::{Config, ConfigManager, TranscriptionProvider};
+use crate::logging::{record_text_pipeline, PipelineStepRecord, TextPipelineRecord};
+use crate::whisper::WhisperVadOptions;
+use anyhow::{anyhow, Result};
+use tracing::{info, warn};
+
+/// A backend in the chain, or why it could not be set up
+enum Link {
+    Ready(Box<TranscriptionBackend>),
+    Unavailable {
+        provider: TranscriptionProvider,
+        error: String,
+    },
+}
+
+impl Link {
+    fn provider(&self) -> TranscriptionProvider {
+        match self {
+            Link::Ready(backend) => backend.provider(),
+            Link::Unavailable { provider, .. } => provider.clone(),
+        }
+    }
+}
+
+/// The primary transcription backend followed by the `fallback` providers, which get the
+/// same audio when the one before them fails, is unreachable, or returns no usable text.
+/// Output the hallucination guard drops counts as no usable text.
+pub struct TranscriptionChain {
+    links: Vec<Link>,
+    guard: Option<HallucinationGuard>,
+}
+
+impl TranscriptionChain {
+    /// Without fallbacks a provider that cannot be built is an error, as before. With
+    /// fallbacks it is skipped (e.g. a missing API key) as long as one provider remains.
+    pub fn build(
+        config_manager: &ConfigManager,
+        config: &Config,
+        vad: WhisperVadOptions,
+    ) -> Result<Self> {
+        let providers = config.transcription.provider_chain();
+        let has_fallbacks = providers.len() > 1;
+
+        let mut links = Vec::with_capacity(providers.len());
+        for provider in providers {
+            match TranscriptionBackend::build(config_manager, config, &provider, vad.clone()) {
+                Ok(backend) => links.push(Link::Ready(Box::new(backend))),
+                Err(err) if has_fallbacks => {
+                    warn!(
+                        "{} transcription is unavailable and will be skipped: {:#}",
+                        provider.label(),
+                        err
+                    );
+                    links.push(Link::Unavailable {
+                        provider,
+                        error: format!("{:#}", err),
+                    });
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        let chain = Self {
+            links,
+            guard: HallucinationGuard::new(&config.transcription.hallucination_guard),
+        };
+        chain.ensure_ready()?;
+        Ok(chain)
+    }
+
+    pub fn initialize(&mut self) -> Result<()> {
+        if self.links.len() == 1 {
+            if let Link::Ready(backend) = &self.links[0] {
+                return backend.initialize();
+            }
+        }
+
+        for link in &mut self.links {
+            let Link::Ready(backend) = link else {
+                continue;
+            };
+            if let Err(err) = backend.initialize() {
+                let provider = backend.provider();
+                warn!(
+                    "{} transcription failed to initialize and will be skipped: {:#}",
+                    provider.label(),
+                    err
+                );
+                *link = Link::Unavailable {
+                    provider,
+                    error: format!("{:#}", err),
+                };
+            }
+        }
+
+        self.ensure_ready()?;
+        if self.links.len() > 1 {
+            info!("   Fallback order: {}", self.describe());
+        }
+        Ok(())
+    }
+
+    fn ensure_ready(&self) -> Result<()> {
+        if self.links.iter().any(|link| matches!(link, Link::Ready(_))) {
+            return Ok(());
+        }
+
+        let reasons = self
+            .links
+            .iter()
+            .filter_map(|link| match link {
+                Link::Unavailable { provider, error } => {
+                    Some(format!("{}: {}", provider.label(), error))
+                }
+                Link::Ready(_) => None,
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        Err(anyhow!(
+            "No transcription provider is available ({})",
+            reasons
+        ))
+    }
+
+    fn describe(&self) -> String {
+        self.links
+            .iter()
+            .map(|link| match link {
+                Link::Ready(backend) => backend.provider().label().to_string(),
+                Link::Unavailable { provider, .. } => format!("{} (unavailable)", provider.label()),
+            })
+            .collect::<Vec<_>>()
+            .join(" → ")
+    }
+
+    /// The configured primary provider
+    pub fn provider(&self) -> TranscriptionProvider {
+        self.links[0].provider()
+    }
+
+    pub fn needs_refresh(current: &Config, new: &Config) -> bool {
+        let providers = new.transcription.provider_chain();
+        current.transcription.provider_chain() != providers
+            || current.transcription.hallucination_guard != new.transcription.hallucination_guard
+            || providers
+                .iter()
+                .any(|provider| TranscriptionBackend::needs_refresh(current, new, provider))
+    }
+
+    /// Transcribe with the first backend that produces usable text, returning which one did.
+    /// When every backend answers but none has usable text, the first answer is returned.
+    pub async fn transcribe(
+        &self,
+        audio_data: Vec<f32>,
+        task: TranscriptionTask,
+    ) -> Result<(TranscriptionProvider, TranscriptionResult)> {
+        let backends = self
+            .links
+            .iter()
+            .filter_map(|link| match link {
+                Link::Ready(backend) => Some(backend),
+                Link::Unavailable { .. } => None,
+            })
+            .collect::<Vec<_>>();
+
+        let audio_secs = audio_data.len() as f32 / 16000.0;
+        let mut audio_data = Some(audio_data);
+        let mut unusable: Option<(TranscriptionProvider, TranscriptionResult)> = None;
+        let mut last_error = None;
+
+        for (index, backend) in backends.iter().enumerate() {
+            let provider = backend.provider();
+            let next = backends.get(index + 1);
+            // Only the last attempt can take the samples without copying them
+            let audio = match next {
+                Some(_) => audio_data.clone().unwrap_or_default(),
+                None => audio_data.take().unwrap_or_default(),
+            };
+
+            let attempt = backend
+                .transcribe(audio, task)
+                .await
+                .map(|result| self.guard(&provider, result, audio_secs));
+            match attempt {
+                Ok(result) if is_usable(&result.text) => {
+                    if index > 0 {
+                        info!(
+                            "↪️  {} transcribed the audio after earlier providers failed",
+                            provider.label()
+                        );
+                    }
+                    return Ok((provider, result));
+                }
+                Ok(result) => {
+                    if next.is_some() {
+                        warn!("{} returned no usable text", provider.label());
+                    }
+                    unusable.get_or_insert((provider.clone(), result));
+                }
+                Err(err) => {
+                    if next.is_some() {
+                        warn!("{} transcription failed: {:#}", provider.label(), err);
+                    }
+                    last_error = Some(err);
+                }
+            }
+
+            if let Some(next) = next {
+                info!("↪️  Retrying with {}", next.provider().label());
+            }
+        }
+
+        if let Some(result) = unusable {
+            return Ok(result);
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!("No transcription provider is available")))
+    }
+
+    /// Run the hallucination guard over a backend's output, logging what it cut and why
+    fn guard(
+        &self,
+        provider: &TranscriptionProvider,
+        mut result: TranscriptionResult,
+        audio_secs: f32,
+    ) -> TranscriptionResult {
+        let Some(guard) = &self.guard else {
+            return result;
+        };
+        let interventions = guard.check(&result.text, audio_secs);
+        let Some(output) = interventions.last().map(|step| step.after.clone()) else {
+            return result;
+        };
+
+        for intervention in &interventions {
+            warn!(
+                "🚫 {} output looks hallucinated, {}: {}",
+                provider.label(),
+                if intervention.after.is_empty() {
+                    "dropped"
+                } else {
+                    "truncated"
+                },
+                intervention.reason
+            );
+        }
+
+        let steps = interventions
+            .into_iter()
+            .map(|step| {
+                PipelineStepRecord::new(
+                    format!("hallucination_guard ({})", step.reason),
+                    step.before,
+                    step.after,
+                    None,
+                )
+            })
+            .collect();
+        record_text_pipeline(TextPipelineRecord::new(
+            std::mem::take(&mut result.text),
+            output.clone(),
+            steps,
+        ));
+
+        if output.is_empty() {
+            result.segments.clear();
+        }
+        result.text = output;
+        result
+    }
+}
+
+/// Output needs at least one letter or digit; empty text or stray punctuation is not usable
+fn is_usable(text: &str) -> bool {
+    text.chars().any(char::is_alphanumeric)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{OpenAiCompatibleConfig, UploadFormat};
+    use crate::transcription::OpenAiCompatibleTranscriber;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// OpenAI-compatible backend whose server answers every request with `status` and `body`
+    async fn stub_backend(status: u16, body: &'static str) -> Link {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut raw = Vec::new();
+                let mut buf = [0u8; 8192];
+                loop {
+                    let read = stream.read(&mut buf).await.unwrap();
+                    raw.extend_from_slice(&buf[..read]);
+                    if String::from_utf8_lossy(&raw).trim_end().ends_with("--") || read == 0 {
+                        break;
+                    }
+                }
+                let reply = format!(
+                    "HTTP/1.1 {status} Stub\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(reply.as_bytes()).await.unwrap();
+                stream.shutdown().await.unwrap();
+            }
+        });
+
+        let config = OpenAiCompatibleConfig {
+            base_url,
+            ..Default::default()
+        };
+        let transcriber = OpenAiCompatibleTranscriber::new(
+            &config,
+            Duration::from_secs(5),
+            0,
+            UploadFormat::Wav,
+            String::new(),
+            None,
+        )
+        .unwrap();
+        Link::Ready(Box::new(TranscriptionBackend::OpenAiCompatible(
+            transcriber,
+        )))
+    }
+
+    fn chain(links: Vec<Link>) -> TranscriptionChain {
+        TranscriptionChain { links, guard: None }
+    }
+
+    async fn transcribe(chain: &TranscriptionChain) -> Result<String> {
+        chain
+            .transcribe(vec![0.1; 1600], TranscriptionTask::Transcribe)
+            .await
+            .map(|(_, result)| result.text)
+    }
+
+    #[tokio::test]
+    async fn failed_providers_fall_through_to_the_next() {
+        let chain = chain(vec![
+            stub_backend(400, r#"{"error":{"message":"bad key"}}"#).await,
+            stub_backend(200, r#"{"text":"hello world"}"#).await,
+        ]);
+        assert_eq!(transcribe(&chain).await.unwrap(), "hello world");
+    }
+
+    #[tokio::test]
+    async fn unusable_text_falls_through_to_the_next() {
+        let chain = chain(vec![
+            stub_backend(200, r#"{"text":" ... "}"#).await,
+            stub_backend(200, r#"{"text":"hello world"}"#).await,
+        ]);
+        assert_eq!(transcribe(&chain).await.unwrap(), "hello world");
+    }
+
+    #[tokio::test]
+    async fn first_unusable_answer_wins_when_every_provider_fails() {
+        let chain = chain(vec![
+            stub_backend(200, r#"{"text":"..."}"#).await,
+            stub_backend(400, r#"{"error":{"message":"bad key"}}"#).await,
+            stub_backend(200, r#"{"text":"?!"}"#).await,
+        ]);
+        assert_eq!(transcribe(&chain).await.unwrap(), "...");
+    }
+
+    #[tokio::test]
+    async fn last_error_is_returned_when_no_provider_answers() {
+        let chain = chain(vec![
+            stub_backend(400, r#"{"error":{"message":"bad key"}}"#).await,
+            stub_backend(400, r#"{"error":{"message":"unknown model"}}"#).await,
+        ]);
+        let err = transcribe(&chain).await.unwrap_err();
+        assert!(format!("{err:#}").contains("unknown model"), "{err:#}");
+    }
+
+    #[tokio::test]
+    async fn unavailable_links_are_skipped() {
+        let chain = chain(vec![
+            Link::Unavailable {
+                provider: TranscriptionProvider::Groq,
+                error: "GROQ_API_KEY environment variable is not set".to_string(),
+            },
+            stub_backend(200, r#"{"text":"hello world"}"#).await,
+        ]);
+        assert!(chain.ensure_ready().is_ok());
+        assert_eq!(chain.provider(), TranscriptionProvider::Groq);
+
+        let (provider, result) = chain
+            .transcribe(vec![0.1; 1600], TranscriptionTask::Transcribe)
+            .await
+            .unwrap();
+        assert_eq!(provider, TranscriptionProvider::OpenAiCompatible);
+        assert_eq!(result.text, "hello world");
+    }
+
+    #[test]
+    fn text_without_letters_or_digits_is_not_usable() {
+        assert!(is_usable("hello"));
+        assert!(is_usable("42"));
+        assert!(!is_usable(""));
+        assert!(!is_usable("  \n"));
+        assert!(!is_usable("... ?!"));
+    }
+}
@@ -0,0 +1,400 @@
+//! Minimal FLAC encoder for 16-bit mono PCM.
+//!
+//! Each block is coded with the best of FLAC's fixed polynomial predictors (orders 0-4)
+//! and a partitioned Rice residual, falling back to constant or verbatim subframes.
+//! That stays close to ffmpeg's output size on speech without needing LPC analysis.
+
+const BLOCK_SIZE: usize = 4096;
+const BITS_PER_SAMPLE: u32 = 16;
+const MAX_FIXED_ORDER: usize = 4;
+const MAX_PARTITION_ORDER: u32 = 6;
+/// 4-bit Rice parameters; 15 is reserved as the escape code
+const MAX_RICE_PARAM: u32 = 14;
+
+/// Encode samples as a complete FLAC stream (`fLaC` marker, STREAMINFO, frames).
+pub(crate) fn encode(samples: &[i16], sample_rate: u32) -> Vec<u8> {
+    let mut out = Vec::with_capacity(samples.len() + 64);
+    out.extend_from_slice(b"fLaC");
+    write_stream_info(&mut out, samples.len() as u64, sample_rate);
+
+    for (frame_number, block) in samples.chunks(BLOCK_SIZE).enumerate() {
+        write_frame(&mut out, frame_number as u64, block, sample_rate);
+    }
+
+    out
+}
+
+fn write_stream_info(out: &mut Vec<u8>, total_samples: u64, sample_rate: u32) {
+    let mut bits = BitWriter::default();
+    // Metadata block header: last block, type 0 (STREAMINFO), 34 bytes
+    bits.write(1, 1);
+    bits.write(7, 0);
+    bits.write(24, 34);
+
+    bits.write(16, BLOCK_SIZE as u64); // min block size
+    bits.write(16, BLOCK_SIZE as u64); // max block size
+    bits.write(24, 0); // min frame size (unknown)
+    bits.write(24, 0); // max frame size (unknown)
+    bits.write(20, u64::from(sample_rate));
+    bits.write(3, 0); // channels - 1
+    bits.write(5, u64::from(BITS_PER_SAMPLE - 1));
+    bits.write(36, total_samples);
+    // MD5 of the decoded audio; all zeros means "not computed"
+    bits.write(64, 0);
+    bits.write(64, 0);
+
+    out.extend_from_slice(&bits.finish());
+}
+
+fn write_frame(out: &mut Vec<u8>, frame_number: u64, block: &[i16], sample_rate: u32) {
+    let mut bits = BitWriter::default();
+
+    // Frame header
+    bits.write(14, 0b11_1111_1111_1110); // sync code
+    bits.write(1, 0); // reserved
+    bits.write(1, 0); // fixed block size stream
+    let full_block = block.len() == BLOCK_SIZE;
+    bits.write(4, if full_block { 0b1100 } else { 0b0111 }); // 4096, or 16-bit size below
+    bits.write(4, sample_rate_code(sample_rate));
+    bits.write(4, 0); // mono
+    bits.write(3, 0b100); // 16 bits per sample
+    bits.write(1, 0); // reserved
+    write_utf8_number(&mut bits, frame_number);
+    if !full_block {
+        bits.write(16, (block.len() - 1) as u64);
+    }
+    let header_crc = crc8(bits.bytes());
+    bits.write(8, u64::from(header_crc));
+
+    let samples = block.iter().map(|&s| i64::from(s)).collect::<Vec<_>>();
+    write_subframe(&mut bits, &samples);
+
+    let mut frame = bits.finish();
+    let footer_crc = crc16(&frame);
+    frame.extend_from_slice(&footer_crc.to_be_bytes());
+    out.extend_from_slice(&frame);
+}
+
+fn sample_rate_code(sample_rate: u32) -> u64 {
+    match sample_rate {
+        8000 => 0b0100,
+        16000 => 0b0101,
+        22050 => 0b0110,
+        24000 => 0b0111,
+        32000 => 0b1000,
+        44100 => 0b1001,
+        48000 => 0b1010,
+        96000 => 0b1011,
+        _ => 0b0000, // taken from STREAMINFO
+    }
+}
+
+fn write_subframe(bits: &mut BitWriter, samples: &[i64]) {
+    if samples.iter().all(|&sample| sample == samples[0]) {
+        bits.write(8, 0b0000_0000); // CONSTANT
+        write_signed(bits, BITS_PER_SAMPLE, samples[0]);
+        return;
+    }
+
+    let verbatim_bits = samples.len() as u64 * u64::from(BITS_PER_SAMPLE);
+    let best = (0..=MAX_FIXED_ORDER.min(samples.len() - 1))
+        .map(|order| {
+            let residual = fixed_residual(samples, order);
+            let plan = RicePlan::choose(&residual, order, samples.len());
+            (order, residual, plan)
+        })
+        .min_by_key(|(order, _, plan)| *order as u64 * u64::from(BITS_PER_SAMPLE) + plan.bits);
+
+    match best {
+        Some((order, residual, plan))
+            if (order as u64 * u64::from(BITS_PER_SAMPLE) + plan.bits) < verbatim_bits =>
+        {
+            bits.write(8, (0b00_1000 | order as u64) << 1); // FIXED, no wasted bits
+            for &sample in &samples[..order] {
+                write_signed(bits, BITS_PER_SAMPLE, sample);
+            }
+            plan.write(bits, &residual, order, samples.len());
+        }
+        _ => {
+            bits.write(8, 0b0000_0010); // VERBATIM
+            for &sample in samples {
+                write_signed(bits, BITS_PER_SAMPLE, sample);
+            }
+        }
+    }
+}
+
+/// Residual of the fixed polynomial predictor of `order`, for samples after the warm-up
+fn fixed_residual(samples: &[i64], order: usize) -> Vec<i64> {
+    (order..samples.len())
+        .map(|i| {
+            let s = |back: usize| samples[i - back];
+            match order {
+                0 => s(0),
+                1 => s(0) - s(1),
+                2 => s(0) - 2 * s(1) + s(2),
+                3 => s(0) - 3 * s(1) + 3 * s(2) - s(3),
+                _ => s(0) - 4 * s(1) + 6 * s(2) - 4 * s(3) + s(4),
+            }
+        })
+        .collect()
+}
+
+/// Maps signed residuals onto unsigned values for Rice coding
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Partition order and per-partition Rice parameters for one residual
+struct RicePlan {
+    partition_order: u32,
+    params: Vec<u32>,
+    /// Estimated size of the coded residual section
+    bits: u64,
+}
+
+impl RicePlan {
+    fn choose(residual: &[i64], predictor_order: usize, block_len: usize) -> Self {
+        let mut best: Option<RicePlan> = None;
+
+        for partition_order in 0..=MAX_PARTITION_ORDER {
+            let partitions = 1usize << partition_order;
+            // Partition sizes must divide the block and the first must hold more than the warm-up
+            if !block_len.is_multiple_of(partitions) || block_len / partitions <= predictor_order {
+                break;
+            }
+
+            let mut params = Vec::with_capacity(partitions);
+            let mut bits = 2 + 4; // coding method + partition order
+            let mut start = 0;
+            for partition in 0..partitions {
+                let len = block_len / partitions - if partition == 0 { predictor_order } else { 0 };
+                let sum = residual[start..start + len]
+                    .iter()
+                    .map(|&r| zigzag(r))
+                    .sum::<u64>();
+                let (param, cost) = best_param(sum, len as u64);
+                params.push(param);
+                bits += 4 + cost;
+                start += len;
+            }
+
+            if best.as_ref().is_none_or(|plan| bits < plan.bits) {
+                best = Some(RicePlan {
+                    partition_order,
+                    params,
+                    bits,
+                });
+            }
+        }
+
+        best.expect("partition order 0 is always valid")
+    }
+
+    fn write(
+        &self,
+        bits: &mut BitWriter,
+        residual: &[i64],
+        predictor_order: usize,
+        block_len: usize,
+    ) {
+        bits.write(2, 0b00); // Rice coding with 4-bit parameters
+        bits.write(4, u64::from(self.partition_order));
+
+        let partitions = self.params.len();
+        let mut start = 0;
+        for (partition, &param) in self.params.iter().enumerate() {
+            let len = block_len / partitions - if partition == 0 { predictor_order } else { 0 };
+            bits.write(4, u64::from(param));
+            for &value in &residual[start..start + len] {
+                let value = zigzag(value);
+                bits.write_unary(value >> param);
+                bits.write(param, value & ((1 << param) - 1));
+            }
+            start += len;
+        }
+    }
+}
+
+/// Rice parameter with the smallest estimated size for `count` values summing to `sum`
+fn best_param(sum: u64, count: u64) -> (u32, u64) {
+    if count == 0 {
+        return (0, 0);
+    }
+
+    let mean = sum / count;
+    let guess = (u64::BITS - mean.leading_zeros()).min(MAX_RICE_PARAM);
+    (guess.saturating_sub(1)..=(guess + 1).min(MAX_RICE_PARAM))
+        .map(|param| (param, count * u64::from(param + 1) + (sum >> param)))
+        .min_by_key(|(_, cost)| *cost)
+        .expect("non-empty parameter range")
+}
+
+fn write_signed(bits: &mut BitWriter, width: u32, value: i64) {
+    bits.write(width, (value as u64) & ((1 << width) - 1));
+}
+
+/// Frame numbers use the UTF-8 style variable length code from the FLAC spec
+fn write_utf8_number(bits: &mut BitWriter, value: u64) {
+    if value < 0x80 {
+        bits.write(8, value);
+        return;
+    }
+
+    let extra_bytes = match value {
+        0..=0x7FF => 1,
+        0x800..=0xFFFF => 2,
+        0x1_0000..=0x1F_FFFF => 3,
+        0x20_0000..=0x3FF_FFFF => 4,
+        0x400_0000..=0x7FFF_FFFF => 5,
+        _ => 6,
+    };
+    let lead_marker = (0xFF00u64 >> (extra_bytes + 1)) & 0xFF;
+    bits.write(8, lead_marker | (value >> (6 * extra_bytes)));
+    for byte in (0..extra_bytes).rev() {
+        bits.write(8, 0x80 | ((value >> (6 * byte)) & 0x3F));
+    }
+}
+
+fn crc8(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |mut crc, &byte| {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0u16, |mut crc, &byte| {
+        crc ^= u16::from(byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+/// MSB-first bit writer
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    pending: u64,
+    pending_bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, count: u32, value: u64) {
+        debug_assert!(count <= 64);
+        if count > 32 {
+            self.write(count - 32, value >> 32);
+            self.write(32, value & 0xFFFF_FFFF);
+            return;
+        }
+
+        self.pending = (self.pending << count) | (value & ((1u64 << count) - 1));
+        self.pending_bits += count;
+        while self.pending_bits >= 8 {
+            self.pending_bits -= 8;
+            self.bytes.push((self.pending >> self.pending_bits) as u8);
+        }
+    }
+
+    /// `zeros` zero bits followed by a one
+    fn write_unary(&mut self, mut zeros: u64) {
+        while zeros >= 32 {
+            self.write(32, 0);
+            zeros -= 32;
+        }
+        self.write(zeros as u32 + 1, 1);
+    }
+
+    /// Whole bytes written so far (used for the header CRC)
+    fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Zero-pads to a byte boundary
+    fn finish(mut self) -> Vec<u8> {
+        if self.pending_bits > 0 {
+            self.write(8 - self.pending_bits, 0);
+        }
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(flac: &[u8]) -> (u32, Vec<i16>) {
+        let mut reader = claxon::FlacReader::new(flac).expect("valid FLAC stream");
+        let info = reader.streaminfo();
+        assert_eq!(info.channels, 1);
+        assert_eq!(info.bits_per_sample, 16);
+        let samples = reader
+            .samples()
+            .map(|sample| sample.expect("decodable sample") as i16)
+            .collect();
+        (info.sample_rate, samples)
+    }
+
+    fn speech_like(len: usize) -> Vec<i16> {
+        let mut noise = 0x2545_F491u32;
+        (0..len)
+            .map(|i| {
+                noise ^= noise << 13;
+                noise ^= noise >> 17;
+                noise ^= noise << 5;
+                let t = i as f32 / 16000.0;
+                let tone = (t * 220.0 * std::f32::consts::TAU).sin() * 9000.0
+                    + (t * 1375.0 * std::f32::consts::TAU).sin() * 3000.0;
+                (tone + (noise % 600) as f32 - 300.0) as i16
+            })
+            .collect()
+    }
+
+    #[test]
+    fn round_trips_losslessly() {
+        let mut samples = speech_like(BLOCK_SIZE * 3 + 1234);
+        // Silence, full-scale extremes and a block with a partial tail
+        samples[..BLOCK_SIZE].fill(0);
+        samples[BLOCK_SIZE + 10] = i16::MAX;
+        samples[BLOCK_SIZE + 11] = i16::MIN;
+
+        let flac = encode(&samples, 16000);
+        let (sample_rate, decoded) = decode(&flac);
+        assert_eq!(sample_rate, 16000);
+        assert_eq!(decoded, samples);
+        assert!(flac.len() < samples.len() * 2, "{} bytes", flac.len());
+    }
+
+    #[test]
+    fn encodes_short_and_unusual_inputs() {
+        for samples in [vec![], vec![7], vec![1, -1], speech_like(5)] {
+            let (_, decoded) = decode(&encode(&samples, 22050));
+            assert_eq!(decoded, samples);
+        }
+
+        let (sample_rate, decoded) = decode(&encode(&speech_like(300), 11025));
+        assert_eq!(sample_rate, 11025);
+        assert_eq!(decoded, speech_like(300));
+    }
+
+    #[test]
+    fn frame_numbers_use_multi_byte_codes() {
+        let samples = speech_like(BLOCK_SIZE * 130);
+        let (_, decoded) = decode(&encode(&samples, 16000));
+        assert_eq!(decoded.len(), samples.len());
+        assert_eq!(decoded[BLOCK_SIZE * 129..], samples[BLOCK_SIZE * 129..]);
+    }
+}
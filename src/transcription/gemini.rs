@@ -1,15 +1,14 @@
-use crate::config::GeminiConfig;
-use crate::transcription::audio::{encode_to_flac, EncodedAudio};
+use crate::config::{GeminiConfig, UploadFormat};
+use crate::transcription::audio::{encode_audio, EncodedAudio};
 use crate::transcription::postprocess::clean_transcription;
-use crate::transcription::{BackendMetrics, TranscriptionResult};
+use crate::transcription::retry::{send_with_retry, NetworkTimings};
+use crate::transcription::{BackendMetrics, TranscriptionResult, TranscriptionTask};
 use anyhow::{Context, Result};
 use base64::engine::general_purpose::STANDARD as BASE64;
 use base64::Engine;
 use reqwest::{Client, Url};
 use serde::{Deserialize, Serialize};
-use std::cmp;
 use std::time::{Duration, Instant};
-use tokio::time::sleep;
 use tracing::{info, warn};
 
 #[derive(Clone)]
@@ -18,11 +17,14 @@ pub struct GeminiTranscriber {
     endpoint: Url,
     api_key: String,
     prompt: String,
+    /// `None` asks for the transcript in whatever language is spoken
+    language: Option<String>,
     temperature: f32,
     max_output_tokens: u32,
     model: String,
     request_timeout: Duration,
     max_retries: u32,
+    upload_format: UploadFormat,
 }
 
 impl GeminiTranscriber {
@@ -31,7 +33,9 @@ impl GeminiTranscriber {
         config: &GeminiConfig,
         request_timeout: Duration,
         max_retries: u32,
+        upload_format: UploadFormat,
         prompt: String,
+        language: Option<String>,
     ) -> Result<Self> {
         let trimmed_endpoint = config.endpoint.trim_end_matches('/');
         let endpoint = Url::parse(&format!(
@@ -53,11 +57,13 @@ impl GeminiTranscriber {
             endpoint,
             api_key,
             prompt,
+            language,
             temperature: config.temperature,
             max_output_tokens: config.max_output_tokens,
             model: config.model.clone(),
             request_timeout,
             max_retries,
+            upload_format,
         })
     }
 
@@ -77,11 +83,17 @@ impl GeminiTranscriber {
         "Gemini 2.5 Pro Flash"
     }
 
-    pub async fn transcribe(&self, audio_data: Vec<f32>) -> Result<TranscriptionResult> {
+    pub async fn transcribe(
+        &self,
+        audio_data: Vec<f32>,
+        task: TranscriptionTask,
+    ) -> Result<TranscriptionResult> {
         if audio_data.is_empty() {
             return Ok(TranscriptionResult {
                 text: String::new(),
                 metrics: BackendMetrics::default(),
+                language: None,
+                segments: Vec::new(),
             });
         }
 
@@ -92,13 +104,13 @@ impl GeminiTranscriber {
         );
 
         let encode_start = Instant::now();
-        let encoded = encode_to_flac(&audio_data).await?;
+        let encoded = encode_audio(&audio_data, self.upload_format);
         let audio_payload = BASE64.encode(encoded.data.as_ref());
         let encode_duration = encode_start.elapsed();
         let payload_bytes = audio_payload.len();
 
         let transcribe_start = Instant::now();
-        let (raw, timings) = self.send_with_retry(&encoded, &audio_payload).await?;
+        let (raw, timings) = self.send_with_retry(&encoded, &audio_payload, task).await?;
         let transcription_duration = transcribe_start.elapsed();
         let cleaned = clean_transcription(&raw, &self.prompt);
 
@@ -119,6 +131,8 @@ impl GeminiTranscriber {
         Ok(TranscriptionResult {
             text: cleaned,
             metrics,
+            language: None,
+            segments: Vec::new(),
         })
     }
 
@@ -126,42 +140,27 @@ impl GeminiTranscriber {
         &self,
         audio: &EncodedAudio,
         payload: &str,
+        task: TranscriptionTask,
     ) -> Result<(String, NetworkTimings)> {
-        let attempts = cmp::max(1, self.max_retries.saturating_add(1));
-
-        for attempt in 0..attempts {
-            match self.send_once(audio, payload).await {
-                Ok(result) => return Ok(result),
-                Err(err) => {
-                    if attempt + 1 == attempts {
-                        return Err(err);
-                    }
-
-                    warn!(
-                        attempt = attempt + 1,
-                        max_attempts = attempts,
-                        "Gemini transcription attempt failed: {}",
-                        err
-                    );
-
-                    let backoff = Duration::from_millis(600 * (1 << attempt));
-                    sleep(backoff).await;
-                }
-            }
-        }
-
-        Err(anyhow::anyhow!("Unknown Gemini transcription failure"))
+        send_with_retry(
+            "Gemini",
+            self.max_retries,
+            Duration::from_millis(600),
+            || self.send_once(audio, payload, task),
+        )
+        .await
     }
 
     async fn send_once(
         &self,
         audio: &EncodedAudio,
         payload: &str,
+        task: TranscriptionTask,
     ) -> Result<(String, NetworkTimings)> {
         let mut url = self.endpoint.clone();
         url.query_pairs_mut().append_pair("key", &self.api_key);
 
-        let instruction = build_instruction(&self.prompt);
+        let instruction = build_instruction(&self.prompt, self.language.as_deref(), task);
 
         let body = GeminiRequest {
             contents: vec![GeminiContent {
@@ -224,16 +223,28 @@ impl GeminiTranscriber {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct NetworkTimings {
-    upload: Duration,
-    response: Duration,
-}
-
-fn build_instruction(prompt: &str) -> String {
-    let mut instruction = String::from(
-        "You are a dedicated speech-to-text engine. Return only the verbatim transcription of the provided audio.\n",
-    );
+fn build_instruction(prompt: &str, language: Option<&str>, task: TranscriptionTask) -> String {
+    let mut instruction = String::from(match task {
+        TranscriptionTask::Transcribe => {
+            "You are a dedicated speech-to-text engine. Return only the verbatim transcription of the provided audio.\n"
+        }
+        TranscriptionTask::Translate => {
+            "You are a dedicated speech translation engine. Return only an English translation of the speech in the provided audio, without commentary.\n"
+        }
+    });
+
+    match (task, language) {
+        (TranscriptionTask::Transcribe, Some(language)) => instruction.push_str(&format!(
+            "The speech is in the language with ISO 639-1 code \"{language}\"; transcribe it in that language.\n"
+        )),
+        (TranscriptionTask::Transcribe, None) => instruction.push_str(
+            "Transcribe in the language that is spoken; do not translate.\n",
+        ),
+        (TranscriptionTask::Translate, Some(language)) => instruction.push_str(&format!(
+            "The speech is in the language with ISO 639-1 code \"{language}\".\n"
+        )),
+        (TranscriptionTask::Translate, None) => {}
+    }
 
     if !prompt.trim().is_empty() {
         instruction.push_str("\nTranscription style guidance: ");
@@ -1,24 +1,30 @@
-use crate::config::GroqConfig;
-use crate::transcription::audio::{encode_to_flac, EncodedAudio};
+use crate::config::{GroqConfig, UploadFormat};
+use crate::transcription::audio::{encode_audio, EncodedAudio};
 use crate::transcription::postprocess::clean_transcription;
-use crate::transcription::{BackendMetrics, TranscriptionResult};
+use crate::transcription::retry::{send_with_retry, NetworkTimings};
+use crate::transcription::segments::VerboseTranscription;
+use crate::transcription::{BackendMetrics, TranscriptionResult, TranscriptionTask};
 use anyhow::{Context, Result};
 use reqwest::{multipart, Client, Url};
 use serde::Deserialize;
-use std::cmp;
 use std::time::{Duration, Instant};
-use tokio::time::sleep;
 use tracing::{info, warn};
 
 #[derive(Clone)]
 pub struct GroqTranscriber {
     client: Client,
     endpoint: Url,
+    /// `/audio/translations` next to `endpoint`, when it follows the OpenAI layout
+    translation_endpoint: Option<Url>,
     api_key: String,
     model: String,
+    translation_model: String,
     prompt: String,
+    /// `None` lets Whisper detect the language
+    language: Option<String>,
     request_timeout: Duration,
     max_retries: u32,
+    upload_format: UploadFormat,
 }
 
 impl GroqTranscriber {
@@ -27,7 +33,9 @@ impl GroqTranscriber {
         config: &GroqConfig,
         request_timeout: Duration,
         max_retries: u32,
+        upload_format: UploadFormat,
         prompt: String,
+        language: Option<String>,
     ) -> Result<Self> {
         let endpoint = Url::parse(&config.endpoint)
             .with_context(|| format!("Invalid Groq endpoint: {}", config.endpoint))?;
@@ -42,12 +50,16 @@ impl GroqTranscriber {
 
         Ok(Self {
             client,
+            translation_endpoint: translation_endpoint(&endpoint),
             endpoint,
             api_key,
             model: config.model.clone(),
+            translation_model: config.translation_model.clone(),
             prompt,
+            language,
             request_timeout,
             max_retries,
+            upload_format,
         })
     }
 
@@ -67,11 +79,17 @@ impl GroqTranscriber {
         "Groq Whisper"
     }
 
-    pub async fn transcribe(&self, audio_data: Vec<f32>) -> Result<TranscriptionResult> {
+    pub async fn transcribe(
+        &self,
+        audio_data: Vec<f32>,
+        task: TranscriptionTask,
+    ) -> Result<TranscriptionResult> {
         if audio_data.is_empty() {
             return Ok(TranscriptionResult {
                 text: String::new(),
                 metrics: BackendMetrics::default(),
+                language: None,
+                segments: Vec::new(),
             });
         }
 
@@ -82,14 +100,15 @@ impl GroqTranscriber {
         );
 
         let encode_start = Instant::now();
-        let encoded = encode_to_flac(&audio_data).await?;
+        let encoded = encode_audio(&audio_data, self.upload_format);
         let encode_duration = encode_start.elapsed();
         let encoded_len = encoded.data.len();
 
         let transcribe_start = Instant::now();
-        let (raw, timings) = self.send_with_retry(&encoded).await?;
+        let (mut payload, timings) = self.send_with_retry(&encoded, task).await?;
         let transcription_duration = transcribe_start.elapsed();
-        let cleaned = clean_transcription(&raw, &self.prompt);
+        let cleaned =
+            clean_transcription(payload.text.as_deref().unwrap_or_default(), &self.prompt);
 
         if cleaned.is_empty() {
             warn!("Groq returned empty or non-speech transcription");
@@ -105,52 +124,71 @@ impl GroqTranscriber {
             transcription_duration,
         };
 
+        let segments = if cleaned.is_empty() {
+            Vec::new()
+        } else {
+            payload.take_segments()
+        };
+
         Ok(TranscriptionResult {
             text: cleaned,
             metrics,
+            language: payload.language,
+            segments,
         })
     }
 
-    async fn send_with_retry(&self, audio: &EncodedAudio) -> Result<(String, NetworkTimings)> {
-        let attempts = cmp::max(1, self.max_retries.saturating_add(1));
-
-        for attempt in 0..attempts {
-            match self.send_once(audio).await {
-                Ok(result) => return Ok(result),
-                Err(err) => {
-                    let is_last_attempt = attempt + 1 == attempts;
-                    if is_last_attempt {
-                        return Err(err);
-                    }
-
-                    warn!(
-                        attempt = attempt + 1,
-                        max_attempts = attempts,
-                        "Groq transcription attempt failed: {}",
-                        err
-                    );
-
-                    let backoff = Duration::from_millis(500 * (1 << attempt));
-                    sleep(backoff).await;
-                }
-            }
-        }
-
-        Err(anyhow::anyhow!("Unknown Groq transcription failure"))
+    async fn send_with_retry(
+        &self,
+        audio: &EncodedAudio,
+        task: TranscriptionTask,
+    ) -> Result<(VerboseTranscription, NetworkTimings)> {
+        send_with_retry("Groq", self.max_retries, Duration::from_millis(500), || {
+            self.send_once(audio, task)
+        })
+        .await
     }
 
-    async fn send_once(&self, audio: &EncodedAudio) -> Result<(String, NetworkTimings)> {
+    async fn send_once(
+        &self,
+        audio: &EncodedAudio,
+        task: TranscriptionTask,
+    ) -> Result<(VerboseTranscription, NetworkTimings)> {
+        let (endpoint, model) = match task {
+            TranscriptionTask::Transcribe => (&self.endpoint, &self.model),
+            TranscriptionTask::Translate => {
+                let endpoint = self.translation_endpoint.as_ref().with_context(|| {
+                    format!(
+                        "Groq endpoint {} has no matching /audio/translations endpoint",
+                        self.endpoint
+                    )
+                })?;
+                (endpoint, &self.translation_model)
+            }
+        };
+
+        // verbose_json carries segment timings and the detected language
         let mut form = multipart::Form::new()
-            .text("model", self.model.clone())
-            .text("response_format", "json".to_string())
+            .text("model", model.clone())
+            .text("response_format", "verbose_json")
             .text("temperature", "0");
 
+        // Translations always produce English and have no word timings
+        if task == TranscriptionTask::Transcribe {
+            if let Some(language) = &self.language {
+                form = form.text("language", language.clone());
+            }
+            form = form
+                .text("timestamp_granularities[]", "segment")
+                .text("timestamp_granularities[]", "word");
+        }
+
         if !self.prompt.trim().is_empty() {
             form = form.text("prompt", self.prompt.clone());
         }
 
         let file_part = multipart::Part::stream(audio.data.clone())
-            .file_name("audio.flac")
+            .file_name(audio.file_name())
             .mime_str(audio.content_type)
             .context("Failed to set Groq audio content type")?;
 
@@ -159,7 +197,7 @@ impl GroqTranscriber {
         let request_start = Instant::now();
         let response = self
             .client
-            .post(self.endpoint.clone())
+            .post(endpoint.clone())
             .bearer_auth(&self.api_key)
             .multipart(form)
             .send()
@@ -170,13 +208,13 @@ impl GroqTranscriber {
 
         if response.status().is_success() {
             let parse_start = Instant::now();
-            let payload: GroqTranscriptionResponse = response
+            let payload: VerboseTranscription = response
                 .json()
                 .await
                 .context("Failed to deserialize Groq transcription response")?;
             let response_duration = parse_start.elapsed();
             return Ok((
-                payload.text.unwrap_or_default(),
+                payload,
                 NetworkTimings {
                     upload: upload_duration,
                     response: response_duration,
@@ -199,15 +237,12 @@ impl GroqTranscriber {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct NetworkTimings {
-    upload: Duration,
-    response: Duration,
-}
-
-#[derive(Debug, Deserialize, Default)]
-struct GroqTranscriptionResponse {
-    text: Option<String>,
+/// Swaps a trailing `/transcriptions` path segment for `/translations`
+fn translation_endpoint(endpoint: &Url) -> Option<Url> {
+    let path = endpoint.path().strip_suffix("/transcriptions")?;
+    let mut translation = endpoint.clone();
+    translation.set_path(&format!("{path}/translations"));
+    Some(translation)
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -219,3 +254,21 @@ struct GroqErrorResponse {
 struct GroqErrorDetail {
     message: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::translation_endpoint;
+    use reqwest::Url;
+
+    #[test]
+    fn translation_endpoint_sits_next_to_transcriptions() {
+        let endpoint = Url::parse("https://api.groq.com/openai/v1/audio/transcriptions").unwrap();
+        assert_eq!(
+            translation_endpoint(&endpoint).unwrap().as_str(),
+            "https://api.groq.com/openai/v1/audio/translations"
+        );
+
+        let proxy = Url::parse("https://proxy.internal/groq").unwrap();
+        assert!(translation_endpoint(&proxy).is_none());
+    }
+}
@@ -0,0 +1,300 @@
+use crate::config::HallucinationGuardConfig;
+
+/// Longest phrase, in words, checked for back-to-back repetition
+const MAX_REPEATED_WORDS: usize = 32;
+
+/// Short recordings still get this much audio's worth of text before the length check
+/// applies, so a quick "OK, thanks" is never flagged
+const MIN_LENGTH_CHECK_SECS: f32 = 2.0;
+
+/// One change the guard made to a transcript
+#[derive(Debug, Clone, PartialEq)]
+pub struct Intervention {
+    pub reason: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// Detects text that Whisper-style models produce on silence or near-silent audio:
+/// known phantom phrases, degenerate repetition loops and output far too long for the
+/// recording it came from.
+#[derive(Debug, Clone)]
+pub struct HallucinationGuard {
+    phrases: Vec<Vec<String>>,
+    max_repeats: usize,
+    max_chars_per_second: f32,
+}
+
+impl HallucinationGuard {
+    /// `None` when the guard is turned off
+    pub fn new(config: &HallucinationGuardConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+
+        let phrases = config
+            .phrases
+            .iter()
+            .map(|phrase| normalized_words(phrase))
+            .filter(|words| !words.is_empty())
+            .collect();
+
+        Some(Self {
+            phrases,
+            max_repeats: config.max_repeats,
+            max_chars_per_second: config.max_chars_per_second,
+        })
+    }
+
+    /// Run every check over `text`, in order. The text after the last intervention is the
+    /// guarded transcript; no interventions means the text passed unchanged.
+    pub fn check(&self, text: &str, audio_secs: f32) -> Vec<Intervention> {
+        let mut interventions = Vec::new();
+        let mut current = text.trim().to_string();
+
+        if let Some((after, removed)) = self.strip_phantom_phrases(&current) {
+            interventions.push(Intervention {
+                reason: format!("phantom phrase: \"{}\"", removed.join("\", \"")),
+                before: std::mem::replace(&mut current, after.clone()),
+                after,
+            });
+        }
+
+        if let Some((after, loops)) = self.collapse_repetition(&current) {
+            interventions.push(Intervention {
+                reason: format!(
+                    "repetition loop: {} repeated phrase{} cut",
+                    loops,
+                    if loops == 1 { "" } else { "s" }
+                ),
+                before: std::mem::replace(&mut current, after.clone()),
+                after,
+            });
+        }
+
+        if let Some(reason) = self.too_long(&current, audio_secs) {
+            interventions.push(Intervention {
+                reason,
+                before: std::mem::take(&mut current),
+                after: String::new(),
+            });
+        }
+
+        interventions
+    }
+
+    /// Drop sentences that start with a known phantom phrase, returning the rest and the
+    /// sentences removed
+    fn strip_phantom_phrases(&self, text: &str) -> Option<(String, Vec<String>)> {
+        if self.phrases.is_empty() {
+            return None;
+        }
+
+        let mut kept = Vec::new();
+        let mut removed = Vec::new();
+        for sentence in sentences(text) {
+            let words = normalized_words(sentence);
+            if self.phrases.iter().any(|phrase| words.starts_with(phrase)) {
+                removed.push(sentence.to_string());
+            } else {
+                kept.push(sentence);
+            }
+        }
+
+        if removed.is_empty() {
+            None
+        } else {
+            Some((kept.join(" "), removed))
+        }
+    }
+
+    /// Cut any run of a word or phrase repeated more than `max_repeats` times in a row down
+    /// to its first occurrence, returning the text and how many runs were cut
+    fn collapse_repetition(&self, text: &str) -> Option<(String, usize)> {
+        if self.max_repeats == 0 {
+            return None;
+        }
+
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let keys: Vec<String> = words.iter().map(|word| repetition_key(word)).collect();
+        let mut kept: Vec<&str> = Vec::with_capacity(words.len());
+        let mut loops = 0;
+        let mut index = 0;
+
+        while index < words.len() {
+            let longest = MAX_REPEATED_WORDS.min((words.len() - index) / (self.max_repeats + 1));
+            let repeated = (1..=longest).find_map(|size| {
+                let count = consecutive_repeats(&keys[index..], size);
+                (count > self.max_repeats).then_some((size, count))
+            });
+
+            match repeated {
+                Some((size, count)) => {
+                    kept.extend_from_slice(&words[index..index + size]);
+                    index += size * count;
+                    loops += 1;
+                }
+                None => {
+                    kept.push(words[index]);
+                    index += 1;
+                }
+            }
+        }
+
+        if loops == 0 {
+            None
+        } else {
+            Some((kept.join(" "), loops))
+        }
+    }
+
+    fn too_long(&self, text: &str, audio_secs: f32) -> Option<String> {
+        if self.max_chars_per_second <= 0.0 || text.is_empty() {
+            return None;
+        }
+
+        let chars = text.chars().count();
+        let limit = self.max_chars_per_second * audio_secs.max(MIN_LENGTH_CHECK_SECS);
+        if chars as f32 > limit {
+            Some(format!(
+                "{} characters for {:.1}s of audio (limit {:.0})",
+                chars, audio_secs, limit
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// Split after `.`, `!`, `?` or `…` followed by whitespace, keeping the punctuation
+fn sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((index, ch)) = chars.next() {
+        let at_boundary = matches!(ch, '.' | '!' | '?' | '…')
+            && chars.peek().is_none_or(|(_, next)| next.is_whitespace());
+        if at_boundary {
+            let end = index + ch.len_utf8();
+            let sentence = text[start..end].trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence);
+            }
+            start = end;
+        }
+    }
+
+    let rest = text[start..].trim();
+    if !rest.is_empty() {
+        sentences.push(rest);
+    }
+    sentences
+}
+
+/// Lowercase words without punctuation, so "Thanks for watching!" matches "thanks for watching"
+fn normalized_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(normalize_word)
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+fn normalize_word(word: &str) -> String {
+    word.chars()
+        .filter(|ch| ch.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Compare words case- and punctuation-insensitively, falling back to the word itself when
+/// it is only punctuation
+fn repetition_key(word: &str) -> String {
+    let key = normalize_word(word);
+    if key.is_empty() {
+        word.to_string()
+    } else {
+        key
+    }
+}
+
+/// How many times the first `size` keys occur back to back at the start of `keys`
+fn consecutive_repeats(keys: &[String], size: usize) -> usize {
+    let pattern = &keys[..size];
+    keys.chunks_exact(size)
+        .take_while(|chunk| *chunk == pattern)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guard() -> HallucinationGuard {
+        HallucinationGuard::new(&HallucinationGuardConfig::default()).unwrap()
+    }
+
+    fn guarded(text: &str, audio_secs: f32) -> String {
+        guard()
+            .check(text, audio_secs)
+            .last()
+            .map_or_else(|| text.to_string(), |step| step.after.clone())
+    }
+
+    #[test]
+    fn phantom_phrases_are_dropped() {
+        assert_eq!(guarded("Thanks for watching!", 3.0), "");
+        assert_eq!(guarded("Subtitles by the Amara.org community", 3.0), "");
+        assert_eq!(
+            guarded("Send the report today. Thank you for watching.", 4.0),
+            "Send the report today."
+        );
+
+        let steps = guard().check("Please subscribe!", 2.0);
+        assert_eq!(steps.len(), 1);
+        assert!(steps[0].reason.starts_with("phantom phrase"));
+    }
+
+    #[test]
+    fn ordinary_speech_passes_unchanged() {
+        let text = "Thanks for the notes, I'll watch the recording later.";
+        assert!(guard().check(text, 4.0).is_empty());
+        assert!(guard().check("no, no, no, that's wrong", 2.0).is_empty());
+    }
+
+    #[test]
+    fn credit_words_in_ordinary_sentences_pass_unchanged() {
+        for text in [
+            "Transcribed by the team on Monday.",
+            "Captions by Sarah are ready for review.",
+            "Subtitles by tomorrow would be great.",
+            "Transcription by hand takes too long.",
+        ] {
+            assert!(guard().check(text, 4.0).is_empty(), "{text}");
+        }
+        assert_eq!(guarded("Transcription by CastingWords", 3.0), "");
+    }
+
+    #[test]
+    fn repetition_loops_collapse_to_one_occurrence() {
+        let looped = format!("Let's begin. {}", "I'm going to go. ".repeat(20));
+        assert_eq!(guarded(&looped, 60.0), "Let's begin. I'm going to go.");
+        assert_eq!(guarded("the the the the the the end", 3.0), "the end");
+    }
+
+    #[test]
+    fn text_too_long_for_the_audio_is_dropped() {
+        let varied: String = (0..60).map(|n| format!("token{} ", n)).collect();
+        assert_eq!(guarded(&varied, 1.0), "");
+        assert!(guard().check(&varied, 30.0).is_empty());
+    }
+
+    #[test]
+    fn disabled_guard_is_none() {
+        let config = HallucinationGuardConfig {
+            enabled: false,
+            ..HallucinationGuardConfig::default()
+        };
+        assert!(HallucinationGuard::new(&config).is_none());
+    }
+}
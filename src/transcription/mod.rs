@@ -1,29 +1,60 @@
 mod audio;
+mod fallback;
+mod flac;
 mod gemini;
 mod groq;
+mod hallucination;
+mod openai;
 mod parakeet;
 mod postprocess;
 mod prompt;
+mod retry;
+mod segments;
 
 use crate::config::{Config, ConfigManager, TranscriptionProvider};
 use crate::paths::expand_tilde;
-use crate::whisper::{WhisperManager, WhisperVadOptions};
+#[cfg(feature = "whisper-native")]
+use crate::whisper::NativeWhisper;
+use crate::whisper::{WhisperManager, WhisperServer, WhisperVadOptions};
 use anyhow::{Context, Result};
 use std::env;
 use std::time::Duration;
 
-pub use audio::{encode_to_flac, EncodedAudio};
+pub use audio::{encode_audio, encode_to_flac, encode_to_wav, EncodedAudio};
+pub use fallback::TranscriptionChain;
 pub use gemini::GeminiTranscriber;
 pub use groq::GroqTranscriber;
+pub use hallucination::{HallucinationGuard, Intervention};
+pub use openai::OpenAiCompatibleTranscriber;
 pub use parakeet::ParakeetTranscriber;
 pub use postprocess::{clean_transcription, contains_only_non_speech_markers, is_prompt_artifact};
 pub use prompt::{PromptBlueprint, DEFAULT_PROMPT};
+#[cfg(feature = "whisper-native")]
+pub(crate) use segments::push_token;
+pub(crate) use segments::VerboseTranscription;
+pub use segments::{TranscriptSegment, TranscriptWord};
 
 pub enum TranscriptionBackend {
     Whisper(WhisperManager),
+    /// In-process whisper.cpp, used for `whisper_cpp` when built with `whisper-native`
+    #[cfg(feature = "whisper-native")]
+    WhisperNative(NativeWhisper),
+    /// Long-lived whisper-server, used for `whisper_cpp` when `server.enabled` is set
+    WhisperServer(WhisperServer),
     Groq(GroqTranscriber),
     Gemini(GeminiTranscriber),
     Parakeet(ParakeetTranscriber),
+    OpenAiCompatible(OpenAiCompatibleTranscriber),
+}
+
+/// What a backend should produce from the recorded speech
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TranscriptionTask {
+    /// Text in the language that was spoken
+    #[default]
+    Transcribe,
+    /// English text, whatever language was spoken
+    Translate,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -39,35 +70,31 @@ pub struct BackendMetrics {
 pub struct TranscriptionResult {
     pub text: String,
     pub metrics: BackendMetrics,
+    /// Language the backend detected, when `language` is `auto` and the backend reports it
+    pub language: Option<String>,
+    /// Timed segments (and words, where available); empty when the backend reports none
+    pub segments: Vec<TranscriptSegment>,
 }
 
 impl TranscriptionBackend {
     pub fn build(
         config_manager: &ConfigManager,
         config: &Config,
+        provider: &TranscriptionProvider,
         vad: WhisperVadOptions,
     ) -> Result<Self> {
         let timeout = Duration::from_secs(config.transcription.request_timeout_secs.max(5));
         let retries = config.transcription.max_retries;
+        let upload_format = config.transcription.upload_format;
+        let language = config.transcription.language_code();
 
-        match config.transcription.provider {
-            TranscriptionProvider::WhisperCpp => {
-                let prompt = Self::prompt_for(config, TranscriptionProvider::WhisperCpp);
-                let whisper_cfg = &config.transcription.whisper_cpp;
-                let whisper_binaries =
-                    config_manager.get_whisper_binary_candidates(whisper_cfg.fallback_cli);
-                let manager = WhisperManager::new(
-                    config_manager.get_model_path(),
-                    whisper_binaries,
-                    whisper_cfg.threads,
-                    prompt,
-                    config_manager.get_temp_dir(),
-                    whisper_cfg.gpu_layers,
-                    vad,
-                    whisper_cfg.no_speech_threshold,
-                )?;
-                Ok(Self::Whisper(manager))
+        match provider {
+            TranscriptionProvider::WhisperCpp
+                if config.transcription.whisper_cpp.server.enabled =>
+            {
+                Self::build_whisper_server(config_manager, config, vad, timeout)
             }
+            TranscriptionProvider::WhisperCpp => Self::build_whisper(config_manager, config, vad),
             TranscriptionProvider::Groq => {
                 let prompt = Self::prompt_for(config, TranscriptionProvider::Groq);
                 let api_key = env::var("GROQ_API_KEY")
@@ -77,7 +104,9 @@ impl TranscriptionBackend {
                     &config.transcription.groq,
                     timeout,
                     retries,
+                    upload_format,
                     prompt,
+                    language,
                 )?;
                 Ok(Self::Groq(provider))
             }
@@ -90,7 +119,9 @@ impl TranscriptionBackend {
                     &config.transcription.gemini,
                     timeout,
                     retries,
+                    upload_format,
                     prompt,
+                    language,
                 )?;
                 Ok(Self::Gemini(provider))
             }
@@ -110,39 +141,70 @@ impl TranscriptionBackend {
                 let provider = ParakeetTranscriber::new(par_cfg, model_dir, prompt)?;
                 Ok(Self::Parakeet(provider))
             }
+            TranscriptionProvider::OpenAiCompatible => {
+                let prompt = Self::prompt_for(config, TranscriptionProvider::OpenAiCompatible);
+                let provider = OpenAiCompatibleTranscriber::new(
+                    &config.transcription.openai_compatible,
+                    timeout,
+                    retries,
+                    upload_format,
+                    prompt,
+                    language,
+                )?;
+                Ok(Self::OpenAiCompatible(provider))
+            }
         }
     }
 
     pub fn initialize(&self) -> Result<()> {
         match self {
             TranscriptionBackend::Whisper(manager) => manager.initialize(),
+            #[cfg(feature = "whisper-native")]
+            TranscriptionBackend::WhisperNative(whisper) => whisper.initialize(),
+            TranscriptionBackend::WhisperServer(server) => server.initialize(),
             TranscriptionBackend::Groq(provider) => provider.initialize(),
             TranscriptionBackend::Gemini(provider) => provider.initialize(),
             TranscriptionBackend::Parakeet(provider) => provider.initialize(),
+            TranscriptionBackend::OpenAiCompatible(provider) => provider.initialize(),
         }
     }
 
     pub fn provider(&self) -> TranscriptionProvider {
         match self {
             TranscriptionBackend::Whisper(_) => TranscriptionProvider::WhisperCpp,
+            #[cfg(feature = "whisper-native")]
+            TranscriptionBackend::WhisperNative(_) => TranscriptionProvider::WhisperCpp,
+            TranscriptionBackend::WhisperServer(_) => TranscriptionProvider::WhisperCpp,
             TranscriptionBackend::Groq(_) => TranscriptionProvider::Groq,
             TranscriptionBackend::Gemini(_) => TranscriptionProvider::Gemini,
             TranscriptionBackend::Parakeet(_) => TranscriptionProvider::Parakeet,
+            TranscriptionBackend::OpenAiCompatible(_) => TranscriptionProvider::OpenAiCompatible,
         }
     }
 
-    pub fn needs_refresh(current: &Config, new: &Config) -> bool {
-        if current.transcription.provider != new.transcription.provider {
+    /// Whether the settings `provider` is built from differ between the two configs
+    pub fn needs_refresh(current: &Config, new: &Config, provider: &TranscriptionProvider) -> bool {
+        // Parakeet has no language option, so the setting does not affect it
+        let language_changed = current.transcription.language_code()
+            != new.transcription.language_code()
+            && *provider != TranscriptionProvider::Parakeet;
+        if language_changed {
             return true;
         }
 
-        match new.transcription.provider {
+        match provider {
+            // In server mode the rebuilt backend reattaches to the running whisper-server unless
+            // its launch settings changed, so prompt or language changes don't reload the model
             TranscriptionProvider::WhisperCpp => {
                 current.transcription.whisper_cpp != new.transcription.whisper_cpp
+                    || (new.transcription.whisper_cpp.server.enabled
+                        && current.transcription.request_timeout_secs
+                            != new.transcription.request_timeout_secs)
             }
             TranscriptionProvider::Groq => {
                 current.transcription.request_timeout_secs != new.transcription.request_timeout_secs
                     || current.transcription.max_retries != new.transcription.max_retries
+                    || current.transcription.upload_format != new.transcription.upload_format
                     || current.transcription.groq != new.transcription.groq
                     || Self::prompt_for(current, TranscriptionProvider::Groq)
                         != Self::prompt_for(new, TranscriptionProvider::Groq)
@@ -150,6 +212,7 @@ impl TranscriptionBackend {
             TranscriptionProvider::Gemini => {
                 current.transcription.request_timeout_secs != new.transcription.request_timeout_secs
                     || current.transcription.max_retries != new.transcription.max_retries
+                    || current.transcription.upload_format != new.transcription.upload_format
                     || current.transcription.gemini != new.transcription.gemini
                     || Self::prompt_for(current, TranscriptionProvider::Gemini)
                         != Self::prompt_for(new, TranscriptionProvider::Gemini)
@@ -159,20 +222,120 @@ impl TranscriptionBackend {
                     || Self::prompt_for(current, TranscriptionProvider::Parakeet)
                         != Self::prompt_for(new, TranscriptionProvider::Parakeet)
             }
+            TranscriptionProvider::OpenAiCompatible => {
+                current.transcription.request_timeout_secs != new.transcription.request_timeout_secs
+                    || current.transcription.max_retries != new.transcription.max_retries
+                    || current.transcription.upload_format != new.transcription.upload_format
+                    || current.transcription.openai_compatible
+                        != new.transcription.openai_compatible
+                    || Self::prompt_for(current, TranscriptionProvider::OpenAiCompatible)
+                        != Self::prompt_for(new, TranscriptionProvider::OpenAiCompatible)
+            }
         }
     }
 
-    pub async fn transcribe(&self, audio_data: Vec<f32>) -> Result<TranscriptionResult> {
+    pub async fn transcribe(
+        &self,
+        audio_data: Vec<f32>,
+        task: TranscriptionTask,
+    ) -> Result<TranscriptionResult> {
         match self {
-            TranscriptionBackend::Whisper(manager) => manager.transcribe(audio_data).await,
-            TranscriptionBackend::Groq(provider) => provider.transcribe(audio_data).await,
-            TranscriptionBackend::Gemini(provider) => provider.transcribe(audio_data).await,
-            TranscriptionBackend::Parakeet(provider) => provider.transcribe(audio_data).await,
+            TranscriptionBackend::Whisper(manager) => manager.transcribe(audio_data, task).await,
+            #[cfg(feature = "whisper-native")]
+            TranscriptionBackend::WhisperNative(whisper) => {
+                whisper.transcribe(audio_data, task).await
+            }
+            TranscriptionBackend::WhisperServer(server) => {
+                server.transcribe(audio_data, task).await
+            }
+            TranscriptionBackend::Groq(provider) => provider.transcribe(audio_data, task).await,
+            TranscriptionBackend::Gemini(provider) => provider.transcribe(audio_data, task).await,
+            TranscriptionBackend::Parakeet(provider) => {
+                if task == TranscriptionTask::Translate {
+                    anyhow::bail!("Parakeet cannot translate; use whisper_cpp, groq, gemini or openai_compatible");
+                }
+                provider.transcribe(audio_data).await
+            }
+            TranscriptionBackend::OpenAiCompatible(provider) => {
+                provider.transcribe(audio_data, task).await
+            }
         }
     }
 }
 
 impl TranscriptionBackend {
+    /// Keep the model loaded in-process rather than running whisper-cli per utterance.
+    #[cfg(feature = "whisper-native")]
+    fn build_whisper(
+        config_manager: &ConfigManager,
+        config: &Config,
+        vad: WhisperVadOptions,
+    ) -> Result<Self> {
+        let prompt = Self::prompt_for(config, TranscriptionProvider::WhisperCpp);
+        let whisper_cfg = &config.transcription.whisper_cpp;
+        let whisper = NativeWhisper::new(
+            config_manager.get_model_path(),
+            whisper_cfg.threads,
+            prompt,
+            config.transcription.language_code(),
+            whisper_cfg.gpu_layers,
+            vad,
+            whisper_cfg.no_speech_threshold,
+        )?;
+        Ok(Self::WhisperNative(whisper))
+    }
+
+    #[cfg(not(feature = "whisper-native"))]
+    fn build_whisper(
+        config_manager: &ConfigManager,
+        config: &Config,
+        vad: WhisperVadOptions,
+    ) -> Result<Self> {
+        let prompt = Self::prompt_for(config, TranscriptionProvider::WhisperCpp);
+        let whisper_cfg = &config.transcription.whisper_cpp;
+        let whisper_binaries =
+            config_manager.get_whisper_binary_candidates(whisper_cfg.fallback_cli);
+        let manager = WhisperManager::new(
+            config_manager.get_model_path(),
+            whisper_binaries,
+            whisper_cfg.threads,
+            prompt,
+            config.transcription.language_code(),
+            config_manager.get_temp_dir(),
+            whisper_cfg.gpu_layers,
+            vad,
+            whisper_cfg.no_speech_threshold,
+        )?;
+        Ok(Self::Whisper(manager))
+    }
+
+    fn build_whisper_server(
+        config_manager: &ConfigManager,
+        config: &Config,
+        vad: WhisperVadOptions,
+        timeout: Duration,
+    ) -> Result<Self> {
+        let prompt = Self::prompt_for(config, TranscriptionProvider::WhisperCpp);
+        let whisper_cfg = &config.transcription.whisper_cpp;
+        let server_cfg = &whisper_cfg.server;
+        let server = WhisperServer::new(
+            server_cfg.url.as_deref(),
+            config_manager.get_whisper_server_binary(server_cfg.binary.as_deref()),
+            &server_cfg.host,
+            server_cfg.port,
+            config_manager.get_model_path(),
+            whisper_cfg.threads,
+            prompt,
+            config.transcription.language_code(),
+            whisper_cfg.gpu_layers,
+            vad,
+            whisper_cfg.no_speech_threshold,
+            timeout,
+            Duration::from_secs(server_cfg.startup_timeout_secs.max(1)),
+        )?;
+        Ok(Self::WhisperServer(server))
+    }
+
     fn prompt_for(config: &Config, provider: TranscriptionProvider) -> String {
         match provider {
             TranscriptionProvider::WhisperCpp => {
@@ -187,6 +350,10 @@ impl TranscriptionBackend {
             TranscriptionProvider::Parakeet => {
                 PromptBlueprint::from(config.transcription.parakeet.prompt.as_str()).resolve()
             }
+            TranscriptionProvider::OpenAiCompatible => {
+                PromptBlueprint::from(config.transcription.openai_compatible.prompt.as_str())
+                    .resolve()
+            }
         }
     }
 }
@@ -0,0 +1,511 @@
+use crate::config::{OpenAiCompatibleConfig, OpenAiResponseFormat, UploadFormat};
+use crate::paths::expand_tilde;
+use crate::transcription::audio::{encode_audio, EncodedAudio};
+use crate::transcription::postprocess::clean_transcription;
+use crate::transcription::retry::{send_with_retry, NetworkTimings};
+use crate::transcription::segments::VerboseTranscription;
+use crate::transcription::{BackendMetrics, TranscriptionResult, TranscriptionTask};
+use anyhow::{Context, Result};
+use reqwest::{multipart, Client, Url};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Multipart client for any server speaking OpenAI's `/audio/transcriptions` and
+/// `/audio/translations` API.
+#[derive(Clone)]
+pub struct OpenAiCompatibleTranscriber {
+    client: Client,
+    endpoint: Url,
+    translation_endpoint: Url,
+    api_key: Option<String>,
+    model: String,
+    response_format: OpenAiResponseFormat,
+    extra_fields: BTreeMap<String, String>,
+    prompt: String,
+    /// `None` leaves the language to the server
+    language: Option<String>,
+    request_timeout: Duration,
+    max_retries: u32,
+    upload_format: UploadFormat,
+}
+
+impl OpenAiCompatibleTranscriber {
+    pub fn new(
+        config: &OpenAiCompatibleConfig,
+        request_timeout: Duration,
+        max_retries: u32,
+        upload_format: UploadFormat,
+        prompt: String,
+        language: Option<String>,
+    ) -> Result<Self> {
+        let base_url = config.base_url.trim().trim_end_matches('/');
+        let endpoint = Url::parse(&format!("{base_url}/audio/transcriptions"))
+            .with_context(|| format!("Invalid OpenAI-compatible base URL: {}", config.base_url))?;
+        let translation_endpoint = Url::parse(&format!("{base_url}/audio/translations"))
+            .with_context(|| format!("Invalid OpenAI-compatible base URL: {}", config.base_url))?;
+
+        let client = Client::builder()
+            .user_agent("hyprwhspr-rs (openai-compatible)")
+            .connect_timeout(Duration::from_secs(10))
+            .timeout(request_timeout)
+            .pool_idle_timeout(Duration::from_secs(30))
+            .build()
+            .context("Failed to build OpenAI-compatible HTTP client")?;
+
+        Ok(Self {
+            client,
+            endpoint,
+            translation_endpoint,
+            api_key: Self::resolve_api_key(config)?,
+            model: config.model.clone(),
+            response_format: config.response_format,
+            extra_fields: config.extra_fields.clone(),
+            prompt,
+            language,
+            request_timeout,
+            max_retries,
+            upload_format,
+        })
+    }
+
+    /// `api_key_env` wins over `api_key_file`; neither means an unauthenticated server.
+    fn resolve_api_key(config: &OpenAiCompatibleConfig) -> Result<Option<String>> {
+        let key = if let Some(var) = &config.api_key_env {
+            env::var(var).with_context(|| format!("{var} environment variable is not set"))?
+        } else if let Some(path) = &config.api_key_file {
+            let path = expand_tilde(path);
+            fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read API key file {}", path.display()))?
+        } else {
+            return Ok(None);
+        };
+
+        let key = key.trim();
+        if key.is_empty() {
+            anyhow::bail!("The configured API key for the OpenAI-compatible backend is empty");
+        }
+        Ok(Some(key.to_string()))
+    }
+
+    pub fn initialize(&self) -> Result<()> {
+        info!(
+            "✅ OpenAI-compatible transcription ready (endpoint: {}, model: {}, auth: {}, timeout: {:?})",
+            self.endpoint,
+            self.model,
+            if self.api_key.is_some() { "bearer" } else { "none" },
+            self.request_timeout
+        );
+        Ok(())
+    }
+
+    pub fn provider_name(&self) -> &'static str {
+        "OpenAI-compatible"
+    }
+
+    pub async fn transcribe(
+        &self,
+        audio_data: Vec<f32>,
+        task: TranscriptionTask,
+    ) -> Result<TranscriptionResult> {
+        if audio_data.is_empty() {
+            return Ok(TranscriptionResult {
+                text: String::new(),
+                metrics: BackendMetrics::default(),
+                language: None,
+                segments: Vec::new(),
+            });
+        }
+
+        let duration_secs = audio_data.len() as f32 / 16000.0;
+        info!(
+            provider = self.provider_name(),
+            "🧠 Transcribing {:.2}s of audio via {}", duration_secs, self.endpoint
+        );
+
+        let encode_start = Instant::now();
+        let encoded = encode_audio(&audio_data, self.upload_format);
+        let encode_duration = encode_start.elapsed();
+        let encoded_len = encoded.data.len();
+
+        let transcribe_start = Instant::now();
+        let (mut response, timings) = self.send_with_retry(&encoded, task).await?;
+        let transcription_duration = transcribe_start.elapsed();
+        let cleaned =
+            clean_transcription(response.text.as_deref().unwrap_or_default(), &self.prompt);
+
+        if cleaned.is_empty() {
+            warn!("OpenAI-compatible server returned empty or non-speech transcription");
+        } else {
+            info!("✅ Transcription (OpenAI-compatible): {}", cleaned);
+        }
+
+        let metrics = BackendMetrics {
+            encode_duration: Some(encode_duration),
+            encoded_bytes: Some(encoded_len),
+            upload_duration: Some(timings.upload),
+            response_duration: Some(timings.response),
+            transcription_duration,
+        };
+
+        let segments = if cleaned.is_empty() {
+            Vec::new()
+        } else {
+            response.take_segments()
+        };
+
+        Ok(TranscriptionResult {
+            text: cleaned,
+            metrics,
+            language: response.language,
+            segments,
+        })
+    }
+
+    async fn send_with_retry(
+        &self,
+        audio: &EncodedAudio,
+        task: TranscriptionTask,
+    ) -> Result<(VerboseTranscription, NetworkTimings)> {
+        send_with_retry(
+            self.provider_name(),
+            self.max_retries,
+            Duration::from_millis(500),
+            || self.send_once(audio, task),
+        )
+        .await
+    }
+
+    async fn send_once(
+        &self,
+        audio: &EncodedAudio,
+        task: TranscriptionTask,
+    ) -> Result<(VerboseTranscription, NetworkTimings)> {
+        let endpoint = match task {
+            TranscriptionTask::Transcribe => &self.endpoint,
+            TranscriptionTask::Translate => &self.translation_endpoint,
+        };
+        let mut form = multipart::Form::new()
+            .text("model", self.model.clone())
+            .text("response_format", self.response_format.as_str());
+
+        if !self.prompt.trim().is_empty() {
+            form = form.text("prompt", self.prompt.clone());
+        }
+
+        // A `language` in extra_fields takes precedence over transcription.language.
+        // Translations have no language field; the output is always English.
+        if let (TranscriptionTask::Transcribe, Some(language)) = (task, &self.language) {
+            if !self.extra_fields.contains_key("language") {
+                form = form.text("language", language.clone());
+            }
+        }
+
+        if task == TranscriptionTask::Transcribe
+            && self.response_format == OpenAiResponseFormat::VerboseJson
+        {
+            form = form
+                .text("timestamp_granularities[]", "segment")
+                .text("timestamp_granularities[]", "word");
+        }
+
+        for (name, value) in &self.extra_fields {
+            form = form.text(name.clone(), value.clone());
+        }
+
+        let file_part = multipart::Part::stream(audio.data.clone())
+            .file_name(audio.file_name())
+            .mime_str(audio.content_type)
+            .context("Failed to set OpenAI-compatible audio content type")?;
+
+        form = form.part("file", file_part);
+
+        let mut request = self.client.post(endpoint.clone()).multipart(form);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let request_start = Instant::now();
+        let response = request
+            .send()
+            .await
+            .context("Failed to send OpenAI-compatible transcription request")?;
+
+        let upload_duration = request_start.elapsed();
+        let status = response.status();
+
+        let parse_start = Instant::now();
+        let body = response
+            .text()
+            .await
+            .context("Failed to read OpenAI-compatible transcription response")?;
+
+        if status.is_success() {
+            let transcription = match self.response_format {
+                OpenAiResponseFormat::Text => VerboseTranscription::from_text(body),
+                OpenAiResponseFormat::Json | OpenAiResponseFormat::VerboseJson => {
+                    serde_json::from_str::<VerboseTranscription>(&body)
+                        .context("Failed to deserialize OpenAI-compatible transcription response")?
+                }
+            };
+            let response_duration = parse_start.elapsed();
+            return Ok((
+                transcription,
+                NetworkTimings {
+                    upload: upload_duration,
+                    response: response_duration,
+                },
+            ));
+        }
+
+        let message = serde_json::from_str::<ErrorResponse>(&body)
+            .ok()
+            .and_then(ErrorResponse::message)
+            .unwrap_or_else(|| {
+                let body = body.trim();
+                if body.is_empty() {
+                    format!("Transcription failed with status {status}")
+                } else {
+                    body.chars().take(200).collect()
+                }
+            });
+
+        Err(anyhow::anyhow!(message)
+            .context(format!("OpenAI-compatible request failed ({status})")))
+    }
+}
+
+/// OpenAI reports `{"error":{"message":..}}`; FastAPI-based servers use `{"detail":..}`.
+#[derive(Debug, Deserialize, Default)]
+struct ErrorResponse {
+    error: Option<ErrorDetail>,
+    detail: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct ErrorDetail {
+    message: Option<String>,
+}
+
+impl ErrorResponse {
+    fn message(self) -> Option<String> {
+        self.error
+            .and_then(|err| err.message)
+            .or_else(|| match self.detail? {
+                serde_json::Value::String(detail) => Some(detail),
+                other => Some(other.to_string()),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::task::JoinHandle;
+
+    /// Minimal HTTP server answering each connection with the next canned response.
+    /// Resolves to the raw requests it received.
+    async fn stub_server(responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                requests.push(read_request(&mut stream).await);
+                let reply = format!(
+                    "HTTP/1.1 {status} Stub\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(reply.as_bytes()).await.unwrap();
+                stream.shutdown().await.unwrap();
+            }
+            requests
+        });
+
+        (base_url, handle)
+    }
+
+    async fn read_request(stream: &mut tokio::net::TcpStream) -> String {
+        let mut raw = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let read = stream.read(&mut buf).await.unwrap();
+            raw.extend_from_slice(&buf[..read]);
+            let text = String::from_utf8_lossy(&raw);
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let content_length = text[..header_end].lines().find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length")
+                        .then(|| value.trim().parse::<usize>().ok())?
+                });
+                let complete = match content_length {
+                    Some(length) => raw.len() >= header_end + 4 + length,
+                    None => text.ends_with("0\r\n\r\n"),
+                };
+                if complete || read == 0 {
+                    return text.into_owned();
+                }
+            }
+        }
+    }
+
+    fn transcriber(
+        base_url: String,
+        configure: impl FnOnce(&mut OpenAiCompatibleConfig),
+    ) -> OpenAiCompatibleTranscriber {
+        let mut config = OpenAiCompatibleConfig {
+            base_url,
+            model: "Systran/faster-whisper-small".to_string(),
+            ..Default::default()
+        };
+        configure(&mut config);
+        OpenAiCompatibleTranscriber::new(
+            &config,
+            Duration::from_secs(5),
+            1,
+            UploadFormat::Flac,
+            "Hello.".to_string(),
+            Some("en".to_string()),
+        )
+        .unwrap()
+    }
+
+    fn audio() -> EncodedAudio {
+        EncodedAudio {
+            data: Bytes::from_static(b"fLaC"),
+            content_type: "audio/flac",
+        }
+    }
+
+    #[tokio::test]
+    async fn posts_multipart_form_and_reads_json_text() {
+        let (base_url, server) = stub_server(vec![(200, r#"{"text":"hello world"}"#)]).await;
+        std::env::set_var("HYPRWHSPR_TEST_OPENAI_KEY", "sk-test");
+        let transcriber = transcriber(base_url, |config| {
+            config.api_key_env = Some("HYPRWHSPR_TEST_OPENAI_KEY".to_string());
+            config
+                .extra_fields
+                .insert("language".to_string(), "en".to_string());
+        });
+
+        let (response, _) = transcriber
+            .send_with_retry(&audio(), TranscriptionTask::Transcribe)
+            .await
+            .unwrap();
+        assert_eq!(response.text.as_deref(), Some("hello world"));
+
+        let requests = server.await.unwrap();
+        let request = &requests[0];
+        assert!(request.starts_with("POST /v1/audio/transcriptions "));
+        assert!(request.contains("authorization: Bearer sk-test"));
+        for field in ["model", "response_format", "prompt", "language", "file"] {
+            assert!(
+                request.contains(&format!("name=\"{field}\"")),
+                "missing form field {field}"
+            );
+        }
+        assert_eq!(request.matches("name=\"language\"").count(), 1);
+        assert!(request.contains("Systran/faster-whisper-small"));
+    }
+
+    #[tokio::test]
+    async fn verbose_json_reports_detected_language() {
+        let (base_url, server) = stub_server(vec![(
+            200,
+            r#"{"text":"hallo welt","language":"german","duration":1.5,"segments":[{"start":0.0,"end":1.5,"text":" hallo welt","avg_logprob":-0.3}]}"#,
+        )])
+        .await;
+        let transcriber = transcriber(base_url, |config| {
+            config.response_format = OpenAiResponseFormat::VerboseJson;
+        });
+
+        let (mut response, _) = transcriber
+            .send_with_retry(&audio(), TranscriptionTask::Transcribe)
+            .await
+            .unwrap();
+        assert_eq!(response.text.as_deref(), Some("hallo welt"));
+        assert_eq!(response.language.as_deref(), Some("german"));
+        assert_eq!(response.take_segments()[0].end, 1.5);
+
+        let request = &server.await.unwrap()[0];
+        assert!(request.contains("name=\"language\"\r\n\r\nen\r\n"));
+        assert!(request.contains("name=\"timestamp_granularities[]\"\r\n\r\nword\r\n"));
+    }
+
+    #[tokio::test]
+    async fn translations_use_their_own_endpoint_without_language() {
+        let (base_url, server) = stub_server(vec![(200, r#"{"text":"good morning"}"#)]).await;
+        let transcriber = transcriber(base_url, |_| {});
+
+        let (response, _) = transcriber
+            .send_with_retry(&audio(), TranscriptionTask::Translate)
+            .await
+            .unwrap();
+        assert_eq!(response.text.as_deref(), Some("good morning"));
+
+        let request = &server.await.unwrap()[0];
+        assert!(request.starts_with("POST /v1/audio/translations "));
+        assert!(!request.contains("name=\"language\""));
+    }
+
+    #[tokio::test]
+    async fn text_response_format_returns_body() {
+        let (base_url, server) = stub_server(vec![(200, "plain transcript\n")]).await;
+        let transcriber = transcriber(base_url, |config| {
+            config.response_format = OpenAiResponseFormat::Text;
+        });
+
+        let (response, _) = transcriber
+            .send_with_retry(&audio(), TranscriptionTask::Transcribe)
+            .await
+            .unwrap();
+        assert_eq!(response.text.as_deref(), Some("plain transcript\n"));
+
+        let request = &server.await.unwrap()[0];
+        assert!(!request.contains("authorization:"));
+        assert!(request.contains("\r\n\r\ntext\r\n"));
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors() {
+        let (base_url, server) = stub_server(vec![
+            (503, r#"{"detail":"model is loading"}"#),
+            (200, r#"{"text":"second try"}"#),
+        ])
+        .await;
+        let transcriber = transcriber(base_url, |_| {});
+
+        let (response, _) = transcriber
+            .send_with_retry(&audio(), TranscriptionTask::Transcribe)
+            .await
+            .unwrap();
+        assert_eq!(response.text.as_deref(), Some("second try"));
+        assert_eq!(server.await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn surfaces_error_detail_after_last_attempt() {
+        let (base_url, _server) = stub_server(vec![
+            (400, r#"{"detail":"unsupported model"}"#),
+            (400, r#"{"error":{"message":"still unsupported"}}"#),
+        ])
+        .await;
+        let transcriber = transcriber(base_url, |_| {});
+
+        let err = transcriber
+            .send_with_retry(&audio(), TranscriptionTask::Transcribe)
+            .await
+            .unwrap_err();
+        let message = format!("{err:#}");
+        assert!(message.contains("400"), "{message}");
+        assert!(message.contains("still unsupported"), "{message}");
+    }
+}
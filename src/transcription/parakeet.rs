@@ -1,8 +1,10 @@
 use crate::config::ParakeetConfig;
 use crate::transcription::postprocess::clean_transcription;
-use crate::transcription::{BackendMetrics, TranscriptionResult};
+use crate::transcription::{
+    BackendMetrics, TranscriptSegment, TranscriptWord, TranscriptionResult,
+};
 use anyhow::{Context, Result};
-use parakeet_rs::{ParakeetTDT, TimestampMode, Transcriber};
+use parakeet_rs::{ParakeetTDT, TimedToken, TimestampMode, Transcriber};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
@@ -76,6 +78,8 @@ impl ParakeetTranscriber {
             return Ok(TranscriptionResult {
                 text: String::new(),
                 metrics: BackendMetrics::default(),
+                language: None,
+                segments: Vec::new(),
             });
         }
 
@@ -89,18 +93,17 @@ impl ParakeetTranscriber {
         let model = self.model.clone();
         let prompt = self.prompt.clone();
 
-        let raw_text = tokio::task::spawn_blocking(move || -> Result<String> {
+        let result = tokio::task::spawn_blocking(move || {
             let mut guard = model.blocking_lock();
-            let result = guard
-                .transcribe_samples(audio_data, 16_000, 1, Some(TimestampMode::Sentences))
-                .map_err(|e| anyhow::anyhow!("Parakeet transcription failed: {}", e))?;
-            Ok(result.text)
+            guard
+                .transcribe_samples(audio_data, 16_000, 1, Some(TimestampMode::Words))
+                .map_err(|e| anyhow::anyhow!("Parakeet transcription failed: {}", e))
         })
         .await
         .context("Parakeet TDT worker panicked")??;
 
         let transcription_duration = transcribe_start.elapsed();
-        let cleaned = clean_transcription(&raw_text, &prompt);
+        let cleaned = clean_transcription(&result.text, &prompt);
 
         if cleaned.is_empty() {
             warn!("Parakeet TDT returned empty or non-speech transcription");
@@ -116,9 +119,95 @@ impl ParakeetTranscriber {
             transcription_duration,
         };
 
+        let segments = if cleaned.is_empty() {
+            Vec::new()
+        } else {
+            sentence_segments(&result.tokens)
+        };
+
         Ok(TranscriptionResult {
             text: cleaned,
             metrics,
+            language: None,
+            segments,
         })
     }
 }
+
+/// Group Parakeet's timed words into sentences, which end at `.`, `?` or `!`
+fn sentence_segments(words: &[TimedToken]) -> Vec<TranscriptSegment> {
+    let mut segments = Vec::new();
+    let mut current: Vec<TranscriptWord> = Vec::new();
+
+    for word in words {
+        let ends_sentence = word.text.contains(['.', '?', '!']);
+        current.push(TranscriptWord {
+            text: word.text.clone(),
+            start: word.start,
+            end: word.end,
+            probability: None,
+        });
+
+        if ends_sentence {
+            segments.push(sentence(std::mem::take(&mut current)));
+        }
+    }
+    if !current.is_empty() {
+        segments.push(sentence(current));
+    }
+
+    segments
+}
+
+fn sentence(words: Vec<TranscriptWord>) -> TranscriptSegment {
+    let mut text = String::new();
+    for word in &words {
+        // Parakeet emits punctuation as words of its own
+        let is_punctuation = word.text.chars().all(|c| c.is_ascii_punctuation());
+        if !text.is_empty() && !is_punctuation {
+            text.push(' ');
+        }
+        text.push_str(&word.text);
+    }
+
+    TranscriptSegment {
+        text,
+        start: words.first().map_or(0.0, |word| word.start),
+        end: words.last().map_or(0.0, |word| word.end),
+        avg_logprob: None,
+        no_speech_prob: None,
+        words,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(text: &str, start: f32, end: f32) -> TimedToken {
+        TimedToken {
+            text: text.to_string(),
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn words_group_into_sentences() {
+        let words = [
+            word("Hello", 0.0, 0.4),
+            word("there", 0.4, 0.8),
+            word(".", 0.8, 0.8),
+            word("Ship", 1.5, 1.8),
+            word("it", 1.8, 2.0),
+        ];
+
+        let segments = sentence_segments(&words);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].text, "Hello there.");
+        assert_eq!((segments[0].start, segments[0].end), (0.0, 0.8));
+        assert_eq!(segments[0].words.len(), 3);
+        assert_eq!(segments[1].text, "Ship it");
+        assert_eq!(segments[1].start, 1.5);
+    }
+}
@@ -0,0 +1,53 @@
+use anyhow::Result;
+use std::cmp;
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::warn;
+
+/// Time spent sending a request and reading its response.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct NetworkTimings {
+    pub upload: Duration,
+    pub response: Duration,
+}
+
+/// Run `send` up to `max_retries + 1` times, doubling `base_backoff` between attempts.
+pub(crate) async fn send_with_retry<T, F, Fut>(
+    provider: &str,
+    max_retries: u32,
+    base_backoff: Duration,
+    mut send: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let attempts = cmp::max(1, max_retries.saturating_add(1));
+
+    for attempt in 0..attempts {
+        match send().await {
+            Ok(result) => return Ok(result),
+            Err(err) => {
+                if attempt + 1 == attempts {
+                    return Err(err);
+                }
+
+                warn!(
+                    attempt = attempt + 1,
+                    max_attempts = attempts,
+                    "{} transcription attempt failed: {}",
+                    provider,
+                    err
+                );
+
+                sleep(base_backoff * (1 << attempt)).await;
+            }
+        }
+    }
+
+    Err(anyhow::anyhow!(
+        "Unknown {} transcription failure",
+        provider
+    ))
+}
@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+
+/// A stretch of the transcript with its position in the recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    /// Text as the backend returned it, before prompt-artifact cleanup
+    pub text: String,
+    /// Seconds from the start of the transcribed audio
+    pub start: f32,
+    pub end: f32,
+    /// Mean token log-probability (Whisper-style backends)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_logprob: Option<f32>,
+    /// Whisper's estimate that the segment holds no speech at all
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_speech_prob: Option<f32>,
+    /// Empty when the backend only reports segment timings
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<TranscriptWord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptWord {
+    pub text: String,
+    pub start: f32,
+    pub end: f32,
+    /// Confidence between 0 and 1, when the backend reports one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub probability: Option<f32>,
+}
+
+/// Append a decoder token, starting a new word when the piece begins with a space.
+/// A word's probability is that of its least likely token.
+#[cfg_attr(not(feature = "whisper-native"), allow(dead_code))]
+pub(crate) fn push_token(
+    words: &mut Vec<TranscriptWord>,
+    piece: &str,
+    start: f32,
+    end: f32,
+    probability: Option<f32>,
+) {
+    let starts_word = piece.starts_with(char::is_whitespace) || words.is_empty();
+    let piece = piece.trim();
+    if piece.is_empty() {
+        return;
+    }
+
+    match words.last_mut() {
+        Some(word) if !starts_word => {
+            word.text.push_str(piece);
+            word.end = end;
+            word.probability = match (word.probability, probability) {
+                (Some(current), Some(token)) => Some(current.min(token)),
+                (current, token) => current.or(token),
+            };
+        }
+        _ => words.push(TranscriptWord {
+            text: piece.to_string(),
+            start,
+            end,
+            probability,
+        }),
+    }
+}
+
+/// OpenAI-style `verbose_json` body, as returned by Groq, OpenAI-compatible servers and
+/// whisper-server. Plain `json` bodies parse too, with no segments.
+#[derive(Debug, Deserialize, Default)]
+pub(crate) struct VerboseTranscription {
+    pub text: Option<String>,
+    pub language: Option<String>,
+    #[serde(default)]
+    segments: Vec<VerboseSegment>,
+    /// Sent at the top level when `timestamp_granularities[]=word` was requested
+    #[serde(default)]
+    words: Vec<VerboseWord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerboseSegment {
+    #[serde(default)]
+    text: String,
+    start: f32,
+    end: f32,
+    avg_logprob: Option<f32>,
+    no_speech_prob: Option<f32>,
+    /// whisper-server nests words in their segment
+    #[serde(default)]
+    words: Vec<VerboseWord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerboseWord {
+    word: String,
+    start: f32,
+    end: f32,
+    probability: Option<f32>,
+}
+
+impl From<VerboseWord> for TranscriptWord {
+    fn from(word: VerboseWord) -> Self {
+        Self {
+            text: word.word.trim().to_string(),
+            start: word.start,
+            end: word.end,
+            probability: word.probability,
+        }
+    }
+}
+
+impl VerboseTranscription {
+    /// A bare transcript, for `text` responses
+    pub fn from_text(text: String) -> Self {
+        Self {
+            text: Some(text),
+            ..Self::default()
+        }
+    }
+
+    /// Segments with their words, placing top-level words in the segment they start in
+    pub fn take_segments(&mut self) -> Vec<TranscriptSegment> {
+        let mut segments: Vec<TranscriptSegment> = self
+            .segments
+            .drain(..)
+            .map(|segment| TranscriptSegment {
+                text: segment.text.trim().to_string(),
+                start: segment.start,
+                end: segment.end,
+                avg_logprob: segment.avg_logprob,
+                no_speech_prob: segment.no_speech_prob,
+                words: segment.words.into_iter().map(Into::into).collect(),
+            })
+            .collect();
+
+        let words = std::mem::take(&mut self.words);
+        if segments.is_empty() && !words.is_empty() {
+            // Word granularity alone: one segment spanning the words
+            let words: Vec<TranscriptWord> = words.into_iter().map(Into::into).collect();
+            return vec![TranscriptSegment {
+                text: self.text.clone().unwrap_or_default().trim().to_string(),
+                start: words.first().map_or(0.0, |word| word.start),
+                end: words.last().map_or(0.0, |word| word.end),
+                avg_logprob: None,
+                no_speech_prob: None,
+                words,
+            }];
+        }
+
+        for word in words {
+            let index = segments
+                .iter()
+                .rposition(|segment| segment.start <= word.start)
+                .unwrap_or(0);
+            if let Some(segment) = segments.get_mut(index) {
+                segment.words.push(word.into());
+            }
+        }
+
+        segments
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_merge_into_words() {
+        let mut words = Vec::new();
+        push_token(&mut words, " Hel", 0.0, 0.2, Some(0.9));
+        push_token(&mut words, "lo", 0.2, 0.4, Some(0.6));
+        push_token(&mut words, " world", 0.5, 0.9, Some(0.8));
+        push_token(&mut words, ".", 0.9, 1.0, Some(0.95));
+
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].text, "Hello");
+        assert_eq!((words[0].start, words[0].end), (0.0, 0.4));
+        assert_eq!(words[0].probability, Some(0.6));
+        assert_eq!(words[1].text, "world.");
+        assert_eq!(words[1].end, 1.0);
+    }
+
+    #[test]
+    fn verbose_json_words_land_in_their_segments() {
+        let body = r#"{
+            "text": " Hello there. General Kenobi.",
+            "language": "english",
+            "segments": [
+                {"id": 0, "start": 0.0, "end": 1.2, "text": " Hello there.", "avg_logprob": -0.2, "no_speech_prob": 0.01},
+                {"id": 1, "start": 1.6, "end": 3.0, "text": " General Kenobi.", "avg_logprob": -0.4, "no_speech_prob": 0.02}
+            ],
+            "words": [
+                {"word": "Hello", "start": 0.0, "end": 0.5},
+                {"word": "there", "start": 0.5, "end": 1.2},
+                {"word": "General", "start": 1.6, "end": 2.2},
+                {"word": "Kenobi", "start": 2.2, "end": 3.0}
+            ]
+        }"#;
+        let mut parsed: VerboseTranscription = serde_json::from_str(body).unwrap();
+        let segments = parsed.take_segments();
+
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].text, "Hello there.");
+        assert_eq!(segments[0].avg_logprob, Some(-0.2));
+        assert_eq!(segments[0].words.len(), 2);
+        assert_eq!(segments[1].words[1].text, "Kenobi");
+        assert_eq!(parsed.language.as_deref(), Some("english"));
+    }
+
+    #[test]
+    fn plain_json_has_no_segments() {
+        let mut parsed: VerboseTranscription = serde_json::from_str(r#"{"text":"hello"}"#).unwrap();
+        assert!(parsed.take_segments().is_empty());
+        assert_eq!(parsed.text.as_deref(), Some("hello"));
+    }
+}
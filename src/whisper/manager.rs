@@ -1,14 +1,30 @@
 use crate::transcription::{
-    clean_transcription, contains_only_non_speech_markers, BackendMetrics, TranscriptionResult,
+    clean_transcription, contains_only_non_speech_markers, encode_to_wav, BackendMetrics,
+    TranscriptSegment, TranscriptionResult, TranscriptionTask,
 };
 use anyhow::{anyhow, Context, Result};
 use std::convert::TryFrom;
+use std::ffi::OsString;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Instant;
+use tokio::process::Command;
 use tracing::{debug, info, trace, warn};
 
+/// Distinguishes temp files of transcriptions that overlap within one process
+static NEXT_AUDIO_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Removes the temp WAV (and whisper's .txt beside it) even when the job fails or is aborted
+struct TempAudioFile(PathBuf);
+
+impl Drop for TempAudioFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+        let _ = fs::remove_file(self.0.with_extension("txt"));
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct WhisperVadOptions {
     pub enabled: bool,
@@ -35,9 +51,45 @@ impl WhisperVadOptions {
         }
     }
 
-    fn is_active(&self) -> bool {
+    pub(crate) fn is_active(&self) -> bool {
         self.enabled && self.model_path.is_some()
     }
+
+    /// Silero VAD flags understood by both whisper-cli and whisper-server
+    pub(crate) fn cli_args(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+        if !self.is_active() {
+            return args;
+        }
+
+        if let Some(model_path) = &self.model_path {
+            args.push("--vad".into());
+            args.push("--vad-model".into());
+            args.push(model_path.into());
+
+            args.push("--vad-threshold".into());
+            args.push(format!("{}", self.threshold).into());
+
+            args.push("--vad-min-speech-duration-ms".into());
+            args.push(format!("{}", self.min_speech_ms).into());
+
+            args.push("--vad-min-silence-duration-ms".into());
+            args.push(format!("{}", self.min_silence_ms).into());
+
+            if self.max_speech_s.is_finite() {
+                args.push("--vad-max-speech-duration-s".into());
+                args.push(format!("{}", self.max_speech_s).into());
+            }
+
+            args.push("--vad-speech-pad-ms".into());
+            args.push(format!("{}", self.speech_pad_ms).into());
+
+            args.push("--vad-samples-overlap".into());
+            args.push(format!("{}", self.samples_overlap).into());
+        }
+
+        args
+    }
 }
 
 pub struct WhisperManager {
@@ -45,6 +97,8 @@ pub struct WhisperManager {
     binary_paths: Vec<PathBuf>,
     threads: usize,
     whisper_prompt: String,
+    /// `None` lets whisper detect the language
+    language: Option<String>,
     temp_dir: PathBuf,
     gpu_layers: i32,
     vad: WhisperVadOptions,
@@ -52,11 +106,13 @@ pub struct WhisperManager {
 }
 
 impl WhisperManager {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         model_path: PathBuf,
         binary_paths: Vec<PathBuf>,
         threads: usize,
         whisper_prompt: String,
+        language: Option<String>,
         temp_dir: PathBuf,
         gpu_layers: i32,
         vad: WhisperVadOptions,
@@ -73,6 +129,7 @@ impl WhisperManager {
             binary_paths,
             threads,
             whisper_prompt,
+            language,
             temp_dir,
             gpu_layers,
             vad,
@@ -164,11 +221,17 @@ impl WhisperManager {
         "CPU only (no GPU detected)".to_string()
     }
 
-    pub async fn transcribe(&self, audio_data: Vec<f32>) -> Result<TranscriptionResult> {
+    pub async fn transcribe(
+        &self,
+        audio_data: Vec<f32>,
+        task: TranscriptionTask,
+    ) -> Result<TranscriptionResult> {
         if audio_data.is_empty() {
             return Ok(TranscriptionResult {
                 text: String::new(),
                 metrics: BackendMetrics::default(),
+                language: None,
+                segments: Vec::new(),
             });
         }
 
@@ -176,9 +239,12 @@ impl WhisperManager {
         info!("🧠 Transcribing {:.2}s of audio...", duration_secs);
 
         // Save audio to temporary WAV file
-        let temp_wav = self
-            .temp_dir
-            .join(format!("audio_{}.wav", std::process::id()));
+        let temp_wav = self.temp_dir.join(format!(
+            "audio_{}_{}.wav",
+            std::process::id(),
+            NEXT_AUDIO_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _cleanup = TempAudioFile(temp_wav.clone());
         let encode_start = Instant::now();
         self.save_audio_as_wav(&audio_data, &temp_wav)?;
         let encode_duration = encode_start.elapsed();
@@ -190,13 +256,8 @@ impl WhisperManager {
 
         // Run whisper.cpp CLI
         let transcribe_start = Instant::now();
-        let transcription = self.run_whisper_cli(&temp_wav).await?;
+        let (transcription, detected_language) = self.run_whisper_cli(&temp_wav, task).await?;
         let transcription_duration = transcribe_start.elapsed();
-        let trimmed = transcription.trim();
-        let cleaned_transcription = clean_transcription(trimmed, &self.whisper_prompt);
-
-        // Always clean up after successful transcription pass
-        let _ = fs::remove_file(&temp_wav);
 
         let metrics = BackendMetrics {
             encode_duration: Some(encode_duration),
@@ -206,85 +267,27 @@ impl WhisperManager {
             transcription_duration,
         };
 
-        if cleaned_transcription.is_empty() {
-            if trimmed.is_empty() {
-                warn!("Whisper returned empty transcription");
-            } else if contains_only_non_speech_markers(trimmed) {
-                debug!("Whisper produced only non-speech markers: {}", trimmed);
-            } else {
-                debug!(
-                    "Transcription removed by prompt artifact filter: raw='{}'",
-                    trimmed
-                );
-            }
-            return Ok(TranscriptionResult {
-                text: String::new(),
-                metrics,
-            });
-        }
-
-        if cleaned_transcription != trimmed {
-            debug!(
-                "Stripped prompt artifacts from transcription: raw='{}', cleaned='{}'",
-                transcription, cleaned_transcription
-            );
-        }
-        info!("✅ Transcription: {}", cleaned_transcription);
-
-        Ok(TranscriptionResult {
-            text: cleaned_transcription,
+        Ok(finish_transcription(
+            &transcription,
+            detected_language,
+            // whisper-cli runs with --no-timestamps and only writes plain text
+            Vec::new(),
+            &self.whisper_prompt,
             metrics,
-        })
+        ))
     }
 
     fn save_audio_as_wav(&self, audio_data: &[f32], path: &PathBuf) -> Result<()> {
-        use std::io::Write;
-
-        // Convert f32 samples to i16
-        let samples_i16: Vec<i16> = audio_data
-            .iter()
-            .map(|&sample| (sample * 32767.0).clamp(-32768.0, 32767.0) as i16)
-            .collect();
-
-        // WAV file header
-        let mut file = fs::File::create(path)?;
-
-        let channels: u16 = 1;
-        let sample_rate: u32 = 16000;
-        let bits_per_sample: u16 = 16;
-        let byte_rate = sample_rate * channels as u32 * bits_per_sample as u32 / 8;
-        let block_align = channels * bits_per_sample / 8;
-        let data_size = (samples_i16.len() * 2) as u32;
-
-        // RIFF header
-        file.write_all(b"RIFF")?;
-        file.write_all(&(36 + data_size).to_le_bytes())?;
-        file.write_all(b"WAVE")?;
-
-        // fmt chunk
-        file.write_all(b"fmt ")?;
-        file.write_all(&16u32.to_le_bytes())?; // Chunk size
-        file.write_all(&1u16.to_le_bytes())?; // Audio format (PCM)
-        file.write_all(&channels.to_le_bytes())?;
-        file.write_all(&sample_rate.to_le_bytes())?;
-        file.write_all(&byte_rate.to_le_bytes())?;
-        file.write_all(&block_align.to_le_bytes())?;
-        file.write_all(&bits_per_sample.to_le_bytes())?;
-
-        // data chunk
-        file.write_all(b"data")?;
-        file.write_all(&data_size.to_le_bytes())?;
-
-        // Write samples
-        for sample in samples_i16 {
-            file.write_all(&sample.to_le_bytes())?;
-        }
-
+        fs::write(path, encode_to_wav(audio_data).data)?;
         debug!("Saved audio to WAV: {:?}", path);
         Ok(())
     }
 
-    async fn run_whisper_cli(&self, audio_file: &PathBuf) -> Result<String> {
+    async fn run_whisper_cli(
+        &self,
+        audio_file: &PathBuf,
+        task: TranscriptionTask,
+    ) -> Result<(String, Option<String>)> {
         let mut last_error: Option<anyhow::Error> = None;
         let mut attempted: Vec<PathBuf> = Vec::new();
 
@@ -299,7 +302,7 @@ impl WhisperManager {
 
             attempted.push(binary.clone());
 
-            match self.invoke_whisper(binary, audio_file) {
+            match self.invoke_whisper(binary, audio_file, task).await {
                 Ok(result) => {
                     if last_error.is_some() {
                         info!("Whisper succeeded using fallback binary: {:?}", binary);
@@ -327,8 +330,16 @@ impl WhisperManager {
         Err(last_error.unwrap_or_else(|| anyhow!("All whisper binaries failed. Tried: {}", tried)))
     }
 
-    fn invoke_whisper(&self, binary: &Path, audio_file: &PathBuf) -> Result<String> {
+    /// Returns the transcription and, when auto-detecting, the language whisper picked
+    async fn invoke_whisper(
+        &self,
+        binary: &Path,
+        audio_file: &PathBuf,
+        task: TranscriptionTask,
+    ) -> Result<(String, Option<String>)> {
         let mut cmd = Command::new(binary);
+        // Cancelling a transcription drops this future; take the child down with it
+        cmd.kill_on_drop(true);
 
         // Basic args
         cmd.args(&[
@@ -342,7 +353,7 @@ impl WhisperManager {
                 .ok_or_else(|| anyhow!("Audio path contains invalid UTF-8"))?,
             "--output-txt",
             "--language",
-            "en",
+            self.language.as_deref().unwrap_or("auto"),
             "--threads",
             &self.threads.to_string(),
             "--prompt",
@@ -353,34 +364,12 @@ impl WhisperManager {
         cmd.arg("--no-speech-thold");
         cmd.arg(format!("{}", self.no_speech_threshold));
 
-        if self.vad.is_active() {
-            if let Some(model_path) = &self.vad.model_path {
-                cmd.arg("--vad");
-                cmd.arg("--vad-model");
-                cmd.arg(model_path);
-
-                cmd.arg("--vad-threshold");
-                cmd.arg(format!("{}", self.vad.threshold));
-
-                cmd.arg("--vad-min-speech-duration-ms");
-                cmd.arg(format!("{}", self.vad.min_speech_ms));
-
-                cmd.arg("--vad-min-silence-duration-ms");
-                cmd.arg(format!("{}", self.vad.min_silence_ms));
-
-                if self.vad.max_speech_s.is_finite() {
-                    cmd.arg("--vad-max-speech-duration-s");
-                    cmd.arg(format!("{}", self.vad.max_speech_s));
-                }
-
-                cmd.arg("--vad-speech-pad-ms");
-                cmd.arg(format!("{}", self.vad.speech_pad_ms));
-
-                cmd.arg("--vad-samples-overlap");
-                cmd.arg(format!("{}", self.vad.samples_overlap));
-            }
+        if task == TranscriptionTask::Translate {
+            cmd.arg("--translate");
         }
 
+        cmd.args(self.vad.cli_args());
+
         // GPU control: AUR version uses --no-gpu flag (opposite logic)
         // If gpu_layers == 0, disable GPU. Otherwise let it use GPU by default
         if self.gpu_layers == 0 {
@@ -394,6 +383,7 @@ impl WhisperManager {
 
         let output = cmd
             .output()
+            .await
             .with_context(|| format!("Failed to execute whisper binary at {:?}", binary))?;
 
         // Log whisper output for debugging
@@ -417,6 +407,12 @@ impl WhisperManager {
             ));
         }
 
+        let detected_language = if self.language.is_none() {
+            detected_language(&stderr)
+        } else {
+            None
+        };
+
         // Try to read output txt file
         let txt_file = audio_file.with_extension("txt");
         if txt_file.exists() {
@@ -437,14 +433,81 @@ impl WhisperManager {
                 );
             }
 
-            Ok(transcription.trim().to_string())
+            Ok((transcription.trim().to_string(), detected_language))
         } else {
             // Fallback to stdout
             warn!(
                 "No .txt file created by whisper using {:?}, falling back to stdout",
                 binary
             );
-            Ok(stdout.trim().to_string())
+            Ok((stdout.trim().to_string(), detected_language))
+        }
+    }
+}
+
+/// Strip prompt artifacts and non-speech markers from raw whisper output.
+/// Segments are dropped along with text that turns out to be empty.
+pub(crate) fn finish_transcription(
+    transcription: &str,
+    language: Option<String>,
+    segments: Vec<TranscriptSegment>,
+    whisper_prompt: &str,
+    metrics: BackendMetrics,
+) -> TranscriptionResult {
+    let trimmed = transcription.trim();
+    let cleaned_transcription = clean_transcription(trimmed, whisper_prompt);
+
+    if cleaned_transcription.is_empty() {
+        if trimmed.is_empty() {
+            warn!("Whisper returned empty transcription");
+        } else if contains_only_non_speech_markers(trimmed) {
+            debug!("Whisper produced only non-speech markers: {}", trimmed);
+        } else {
+            debug!(
+                "Transcription removed by prompt artifact filter: raw='{}'",
+                trimmed
+            );
         }
+        return TranscriptionResult {
+            text: String::new(),
+            metrics,
+            language,
+            segments: Vec::new(),
+        };
+    }
+
+    if cleaned_transcription != trimmed {
+        debug!(
+            "Stripped prompt artifacts from transcription: raw='{}', cleaned='{}'",
+            transcription, cleaned_transcription
+        );
+    }
+    info!("✅ Transcription: {}", cleaned_transcription);
+
+    TranscriptionResult {
+        text: cleaned_transcription,
+        metrics,
+        language,
+        segments,
+    }
+}
+
+/// whisper.cpp logs `auto-detected language: de (p = 0.97)` when no language is forced
+fn detected_language(log: &str) -> Option<String> {
+    let (_, rest) = log.split_once("auto-detected language: ")?;
+    let code = rest.split_whitespace().next()?;
+    Some(code.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::detected_language;
+
+    #[test]
+    fn parses_auto_detected_language_from_whisper_log() {
+        let log = "whisper_init_state: compute buffer (decode) =   97.28 MB\n\
+                   whisper_full_with_state: auto-detected language: de (p = 0.972656)\n";
+        assert_eq!(detected_language(log).as_deref(), Some("de"));
+        assert_eq!(detected_language("system_info: n_threads = 4"), None);
     }
 }
@@ -0,0 +1,270 @@
+use super::manager::finish_transcription;
+use super::WhisperVadOptions;
+use crate::transcription::{
+    push_token, BackendMetrics, TranscriptSegment, TranscriptionResult, TranscriptionTask,
+};
+use anyhow::{anyhow, Context, Result};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{debug, info, warn};
+use whisper_rs::{
+    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperVadParams,
+};
+
+/// Tells whisper.cpp to give up once the transcription future is dropped (cancel)
+struct AbortOnDrop(Arc<AtomicBool>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Per-utterance decoding settings, mirroring the whisper-cli arguments of `WhisperManager`
+#[derive(Debug, Clone)]
+struct DecodeOptions {
+    threads: usize,
+    whisper_prompt: String,
+    /// `None` lets whisper detect the language
+    language: Option<String>,
+    vad: WhisperVadOptions,
+    no_speech_threshold: f32,
+}
+
+/// Output of one whisper.cpp run
+struct Decoded {
+    text: String,
+    language: Option<String>,
+    segments: Vec<TranscriptSegment>,
+}
+
+/// whisper.cpp linked in-process through whisper-rs. The ggml model stays loaded
+/// between utterances instead of being reloaded by a whisper-cli process each time.
+pub struct NativeWhisper {
+    context: Arc<WhisperContext>,
+    model_path: PathBuf,
+    use_gpu: bool,
+    options: DecodeOptions,
+}
+
+impl NativeWhisper {
+    pub fn new(
+        model_path: PathBuf,
+        threads: usize,
+        whisper_prompt: String,
+        language: Option<String>,
+        gpu_layers: i32,
+        vad: WhisperVadOptions,
+        no_speech_threshold: f32,
+    ) -> Result<Self> {
+        if !model_path.exists() {
+            return Err(anyhow!("Whisper model not found at: {:?}", model_path));
+        }
+
+        // Route whisper.cpp/ggml logging through tracing instead of stderr
+        whisper_rs::install_logging_hooks();
+
+        // Same rule as the CLI path: gpu_layers == 0 means CPU only
+        let use_gpu = gpu_layers != 0;
+        let mut params = WhisperContextParameters::default();
+        params.use_gpu(use_gpu);
+
+        let load_start = Instant::now();
+        let context = WhisperContext::new_with_params(&model_path, params)
+            .with_context(|| format!("Failed to load whisper model {:?}", model_path))?;
+        debug!(
+            "Loaded whisper model {:?} in {:?}",
+            model_path,
+            load_start.elapsed()
+        );
+
+        Ok(Self {
+            context: Arc::new(context),
+            model_path,
+            use_gpu,
+            options: DecodeOptions {
+                threads,
+                whisper_prompt,
+                language,
+                vad,
+                no_speech_threshold,
+            },
+        })
+    }
+
+    pub fn initialize(&self) -> Result<()> {
+        info!("✅ Whisper initialized (in-process whisper-rs)");
+        info!("   Model: {:?}", self.model_path);
+        if self.use_gpu {
+            info!("   GPU: enabled (if whisper-rs was built with GPU support)");
+        } else {
+            info!("   GPU: disabled (CPU only)");
+        }
+
+        let vad = &self.options.vad;
+        if vad.enabled {
+            if let Some(path) = &vad.model_path {
+                info!("   VAD: enabled ({})", path.display());
+            } else {
+                warn!("   VAD: enabled but model file not found (will run without VAD)");
+            }
+        } else {
+            info!("   VAD: disabled");
+        }
+
+        Ok(())
+    }
+
+    pub async fn transcribe(
+        &self,
+        audio_data: Vec<f32>,
+        task: TranscriptionTask,
+    ) -> Result<TranscriptionResult> {
+        if audio_data.is_empty() {
+            return Ok(TranscriptionResult {
+                text: String::new(),
+                metrics: BackendMetrics::default(),
+                language: None,
+                segments: Vec::new(),
+            });
+        }
+
+        let duration_secs = audio_data.len() as f32 / 16000.0;
+        info!("🧠 Transcribing {:.2}s of audio...", duration_secs);
+
+        let context = Arc::clone(&self.context);
+        let options = self.options.clone();
+        let aborted = Arc::new(AtomicBool::new(false));
+        let _abort = AbortOnDrop(Arc::clone(&aborted));
+
+        let transcribe_start = Instant::now();
+        let decoded =
+            tokio::task::spawn_blocking(move || options.run(&context, &audio_data, task, aborted))
+                .await
+                .context("Whisper transcription task panicked")??;
+        let transcription_duration = transcribe_start.elapsed();
+
+        let metrics = BackendMetrics {
+            transcription_duration,
+            ..BackendMetrics::default()
+        };
+
+        Ok(finish_transcription(
+            &decoded.text,
+            decoded.language,
+            decoded.segments,
+            &self.options.whisper_prompt,
+            metrics,
+        ))
+    }
+}
+
+impl DecodeOptions {
+    fn run(
+        &self,
+        context: &WhisperContext,
+        audio: &[f32],
+        task: TranscriptionTask,
+        aborted: Arc<AtomicBool>,
+    ) -> Result<Decoded> {
+        let mut state = context
+            .create_state()
+            .context("Failed to create whisper state")?;
+
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        params.set_n_threads(self.threads.try_into().unwrap_or(i32::MAX));
+        params.set_language(Some(self.language.as_deref().unwrap_or("auto")));
+        params.set_initial_prompt(&self.whisper_prompt);
+        params.set_no_speech_thold(self.no_speech_threshold);
+        params.set_translate(task == TranscriptionTask::Translate);
+        // Timestamps stay on so segments and words carry their position in the audio
+        params.set_token_timestamps(true);
+        params.set_print_special(false);
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_timestamps(false);
+        params.set_abort_callback_safe(move || aborted.load(Ordering::Relaxed));
+
+        if self.vad.is_active() {
+            if let Some(model_path) = self.vad.model_path.as_ref().and_then(|p| p.to_str()) {
+                let mut vad_params = WhisperVadParams::new();
+                vad_params.set_threshold(self.vad.threshold);
+                vad_params.set_min_speech_duration(self.vad.min_speech_ms as i32);
+                vad_params.set_min_silence_duration(self.vad.min_silence_ms as i32);
+                if self.vad.max_speech_s.is_finite() {
+                    vad_params.set_max_speech_duration(self.vad.max_speech_s);
+                }
+                vad_params.set_speech_pad(self.vad.speech_pad_ms as i32);
+                vad_params.set_samples_overlap(self.vad.samples_overlap);
+
+                params.set_vad_model_path(Some(model_path));
+                params.set_vad_params(vad_params);
+                params.enable_vad(true);
+            }
+        }
+
+        state
+            .full(params, audio)
+            .context("Whisper inference failed")?;
+
+        // Token ids from end-of-text on are special and timestamp tokens
+        let first_special = context.token_eot();
+        let mut transcription = String::new();
+        let mut segments = Vec::new();
+        for segment in state.as_iter() {
+            let text = segment
+                .to_str_lossy()
+                .context("Failed to read whisper segment text")?;
+            transcription.push_str(&text);
+
+            let mut words = Vec::new();
+            let mut logprob_sum = 0.0;
+            let mut token_count = 0;
+            for index in 0..segment.n_tokens() {
+                let Some(token) = segment.get_token(index) else {
+                    continue;
+                };
+                let data = token.token_data();
+                if data.id >= first_special {
+                    continue;
+                }
+                let piece = token
+                    .to_str_lossy()
+                    .context("Failed to read whisper token text")?;
+                // whisper.cpp reports times in centiseconds
+                push_token(
+                    &mut words,
+                    &piece,
+                    data.t0 as f32 / 100.0,
+                    data.t1 as f32 / 100.0,
+                    Some(data.p),
+                );
+                logprob_sum += data.plog;
+                token_count += 1;
+            }
+
+            segments.push(TranscriptSegment {
+                text: text.trim().to_string(),
+                start: segment.start_timestamp() as f32 / 100.0,
+                end: segment.end_timestamp() as f32 / 100.0,
+                avg_logprob: (token_count > 0).then(|| logprob_sum / token_count as f32),
+                no_speech_prob: Some(segment.no_speech_probability()),
+                words,
+            });
+        }
+
+        let detected_language = if self.language.is_none() {
+            whisper_rs::get_lang_str(state.full_lang_id_from_state()).map(str::to_string)
+        } else {
+            None
+        };
+
+        Ok(Decoded {
+            text: transcription.trim().to_string(),
+            language: detected_language,
+            segments,
+        })
+    }
+}
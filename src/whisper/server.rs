@@ -0,0 +1,680 @@
+use super::manager::finish_transcription;
+use super::WhisperVadOptions;
+use crate::transcription::{
+    encode_to_wav, BackendMetrics, TranscriptionResult, TranscriptionTask, VerboseTranscription,
+};
+use anyhow::{anyhow, Context, Result};
+use bytes::Bytes;
+use reqwest::{multipart, Client, StatusCode};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, LazyLock, Mutex, Weak};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+use tracing::{debug, info, warn};
+
+const HEALTH_POLL_INTERVAL: Duration = Duration::from_millis(200);
+const RESTART_BACKOFF_MIN: Duration = Duration::from_millis(500);
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(30);
+/// A server that stayed up this long is considered healthy again, resetting the backoff
+const STABLE_RUN: Duration = Duration::from_secs(60);
+
+/// Managed whisper-servers by address. Only one server can listen on an address, so a
+/// rebuilt backend shares the running one when the launch settings are the same.
+static MANAGED_SERVERS: LazyLock<Mutex<HashMap<String, ManagedServer>>> =
+    LazyLock::new(Default::default);
+
+/// Command line for a managed whisper-server child
+#[derive(Debug, Clone, PartialEq)]
+struct Launch {
+    binary: PathBuf,
+    args: Vec<OsString>,
+}
+
+impl Launch {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        binary: PathBuf,
+        model_path: &Path,
+        host: &str,
+        port: u16,
+        threads: usize,
+        gpu_layers: i32,
+        vad: &WhisperVadOptions,
+        no_speech_threshold: f32,
+    ) -> Self {
+        let mut args: Vec<OsString> = vec![
+            "-m".into(),
+            model_path.into(),
+            "--host".into(),
+            host.into(),
+            "--port".into(),
+            port.to_string().into(),
+            "--threads".into(),
+            threads.to_string().into(),
+            // No --language: every request names its own, so switching it needs no restart
+            "--no-speech-thold".into(),
+            format!("{}", no_speech_threshold).into(),
+        ];
+        args.extend(vad.cli_args());
+
+        // Same rule as whisper-cli: gpu_layers == 0 means CPU only
+        if gpu_layers == 0 {
+            args.push("--no-gpu".into());
+        }
+
+        Self { binary, args }
+    }
+
+    fn spawn(&self) -> Result<Child> {
+        let mut cmd = Command::new(&self.binary);
+        cmd.args(&self.args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            // Dropping the supervisor (daemon shutdown) takes the server down too
+            .kill_on_drop(true);
+
+        debug!("Launching whisper-server: {:?}", cmd);
+        let mut child = cmd
+            .spawn()
+            .with_context(|| format!("Failed to launch whisper-server at {:?}", self.binary))?;
+
+        if let Some(stderr) = child.stderr.take() {
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    debug!("whisper-server: {}", line);
+                }
+            });
+        }
+
+        Ok(child)
+    }
+
+    /// Keep a server running until `stop` fires or its sender is dropped, restarting it with
+    /// backoff when it exits. Returns once the last child has exited.
+    async fn supervise(self, mut stop: oneshot::Receiver<()>) {
+        let mut backoff = RESTART_BACKOFF_MIN;
+        loop {
+            let started = Instant::now();
+            match self.spawn() {
+                Ok(mut child) => {
+                    info!("🚀 whisper-server started (pid {:?})", child.id());
+                    tokio::select! {
+                        status = child.wait() => match status {
+                            Ok(status) => warn!("whisper-server exited ({}), restarting", status),
+                            Err(err) => warn!("Lost track of whisper-server: {}, restarting", err),
+                        },
+                        _ = &mut stop => {
+                            if let Err(err) = child.kill().await {
+                                warn!("Failed to stop whisper-server: {}", err);
+                            }
+                            info!("🛑 whisper-server stopped");
+                            return;
+                        }
+                    }
+                }
+                Err(err) => warn!("{:#}", err),
+            }
+
+            if started.elapsed() >= STABLE_RUN {
+                backoff = RESTART_BACKOFF_MIN;
+            }
+            tokio::select! {
+                _ = sleep(backoff) => {}
+                _ = &mut stop => return,
+            }
+            backoff = (backoff * 2).min(RESTART_BACKOFF_MAX);
+        }
+    }
+}
+
+/// A supervised server in [`MANAGED_SERVERS`]
+struct ManagedServer {
+    launch: Launch,
+    lease: Weak<ServerLease>,
+    /// Finishes once the supervisor has stopped its child
+    supervisor: JoinHandle<()>,
+}
+
+/// Keeps a managed server running while any backend holds it
+struct ServerLease {
+    stop: Mutex<Option<oneshot::Sender<()>>>,
+}
+
+impl ServerLease {
+    fn stop(&self) {
+        self.stop.lock().expect("server lease lock poisoned").take();
+    }
+}
+
+impl ManagedServer {
+    /// The running server at `address` if it was launched the same way, else a new one that
+    /// starts once the server it replaces has exited and freed the address
+    fn acquire(address: &str, launch: Launch) -> Arc<ServerLease> {
+        let mut servers = MANAGED_SERVERS
+            .lock()
+            .expect("server registry lock poisoned");
+
+        let previous = match servers.remove(address) {
+            Some(server) => {
+                match server.lease.upgrade() {
+                    Some(lease) if server.launch == launch => {
+                        debug!("Reusing the whisper-server running at {}", address);
+                        servers.insert(address.to_string(), server);
+                        return lease;
+                    }
+                    // Backends of queued jobs may still hold it; stop it for them too
+                    Some(lease) => {
+                        info!(
+                            "🔄 Restarting whisper-server at {} with new settings",
+                            address
+                        );
+                        lease.stop();
+                    }
+                    None => {}
+                }
+                Some(server.supervisor)
+            }
+            None => None,
+        };
+
+        let (stop_tx, stop_rx) = oneshot::channel();
+        let lease = Arc::new(ServerLease {
+            stop: Mutex::new(Some(stop_tx)),
+        });
+        let supervisor = tokio::spawn({
+            let launch = launch.clone();
+            async move {
+                if let Some(previous) = previous {
+                    let _ = previous.await;
+                }
+                launch.supervise(stop_rx).await;
+            }
+        });
+        servers.insert(
+            address.to_string(),
+            ManagedServer {
+                launch,
+                lease: Arc::downgrade(&lease),
+                supervisor,
+            },
+        );
+        lease
+    }
+}
+
+/// whisper.cpp's HTTP server, either launched and supervised by the daemon or attached to
+/// by URL. The model stays loaded between utterances instead of being reloaded by a
+/// whisper-cli process each time.
+pub struct WhisperServer {
+    client: Client,
+    base_url: String,
+    model_path: PathBuf,
+    whisper_prompt: String,
+    /// `None` lets whisper detect the language
+    language: Option<String>,
+    startup_timeout: Duration,
+    /// Present when the daemon owns the server process
+    managed: Option<Arc<ServerLease>>,
+}
+
+impl WhisperServer {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        url: Option<&str>,
+        binary: Option<PathBuf>,
+        host: &str,
+        port: u16,
+        model_path: PathBuf,
+        threads: usize,
+        whisper_prompt: String,
+        language: Option<String>,
+        gpu_layers: i32,
+        vad: WhisperVadOptions,
+        no_speech_threshold: f32,
+        request_timeout: Duration,
+        startup_timeout: Duration,
+    ) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent("hyprwhspr-rs (whisper-server)")
+            .timeout(request_timeout)
+            .build()
+            .context("Failed to build whisper-server HTTP client")?;
+
+        let (base_url, managed) = match url {
+            Some(url) => (url.trim().trim_end_matches('/').to_string(), None),
+            None => {
+                if !model_path.exists() {
+                    return Err(anyhow!("Whisper model not found at: {:?}", model_path));
+                }
+                let binary = binary.filter(|path| path.exists()).ok_or_else(|| {
+                    anyhow!(
+                        "whisper-server binary not found. Install whisper.cpp's server or set transcription.whisper_cpp.server.binary"
+                    )
+                })?;
+                let launch = Launch::new(
+                    binary,
+                    &model_path,
+                    host,
+                    port,
+                    threads,
+                    gpu_layers,
+                    &vad,
+                    no_speech_threshold,
+                );
+                let address = format!("{}:{}", host, port);
+                let lease = ManagedServer::acquire(&address, launch);
+                (format!("http://{}", address), Some(lease))
+            }
+        };
+
+        Ok(Self {
+            client,
+            base_url,
+            model_path,
+            whisper_prompt,
+            language,
+            startup_timeout,
+            managed,
+        })
+    }
+
+    pub fn initialize(&self) -> Result<()> {
+        if self.managed.is_some() {
+            info!("✅ Whisper initialized (managed whisper-server)");
+            info!("   Model: {:?}", self.model_path);
+        } else {
+            info!("✅ Whisper initialized (attached to whisper-server)");
+        }
+        info!("   Server: {}", self.base_url);
+        Ok(())
+    }
+
+    pub async fn transcribe(
+        &self,
+        audio_data: Vec<f32>,
+        task: TranscriptionTask,
+    ) -> Result<TranscriptionResult> {
+        if audio_data.is_empty() {
+            return Ok(TranscriptionResult {
+                text: String::new(),
+                metrics: BackendMetrics::default(),
+                language: None,
+                segments: Vec::new(),
+            });
+        }
+
+        let duration_secs = audio_data.len() as f32 / 16000.0;
+        info!("🧠 Transcribing {:.2}s of audio...", duration_secs);
+
+        let encode_start = Instant::now();
+        let wav = encode_to_wav(&audio_data).data;
+        let encode_duration = encode_start.elapsed();
+        let encoded_bytes = wav.len();
+
+        let transcribe_start = Instant::now();
+        self.wait_until_ready().await?;
+        let mut transcript = match self.send_once(&wav, task).await {
+            Ok(output) => output,
+            // The server may have crashed mid-request; give the supervisor one chance to bring it back
+            Err(err) if self.managed.is_some() => {
+                warn!("whisper-server request failed: {:#}", err);
+                self.wait_until_ready().await?;
+                self.send_once(&wav, task).await?
+            }
+            Err(err) => return Err(err),
+        };
+        let transcription_duration = transcribe_start.elapsed();
+
+        let metrics = BackendMetrics {
+            encode_duration: Some(encode_duration),
+            encoded_bytes: Some(encoded_bytes),
+            upload_duration: None,
+            response_duration: None,
+            transcription_duration,
+        };
+
+        // The server names the language even when it was forced; only report detections
+        let detected_language = transcript
+            .language
+            .take()
+            .filter(|_| self.language.is_none());
+        let segments = transcript.take_segments();
+        Ok(finish_transcription(
+            transcript.text.as_deref().unwrap_or_default(),
+            detected_language,
+            segments,
+            &self.whisper_prompt,
+            metrics,
+        ))
+    }
+
+    /// Poll `/health` until the server has loaded its model.
+    async fn wait_until_ready(&self) -> Result<()> {
+        let deadline = Instant::now() + self.startup_timeout;
+        let health_url = format!("{}/health", self.base_url);
+
+        loop {
+            let last_status = match self.client.get(&health_url).send().await {
+                // Servers predating /health answer 404 once they are listening
+                Ok(response)
+                    if response.status().is_success()
+                        || response.status() == StatusCode::NOT_FOUND =>
+                {
+                    return Ok(())
+                }
+                Ok(response) => response.status().to_string(),
+                Err(err) => err.to_string(),
+            };
+
+            if Instant::now() >= deadline {
+                return Err(anyhow!(
+                    "whisper-server at {} is not ready after {:?} ({})",
+                    self.base_url,
+                    self.startup_timeout,
+                    last_status
+                ));
+            }
+            sleep(HEALTH_POLL_INTERVAL).await;
+        }
+    }
+
+    async fn send_once(
+        &self,
+        wav: &Bytes,
+        task: TranscriptionTask,
+    ) -> Result<VerboseTranscription> {
+        let file_part = multipart::Part::stream(wav.clone())
+            .file_name("audio.wav")
+            .mime_str("audio/wav")
+            .context("Failed to set whisper-server audio content type")?;
+
+        // verbose_json carries segment timings and the detected language
+        let form = multipart::Form::new()
+            .part("file", file_part)
+            .text("response_format", "verbose_json")
+            .text("temperature", "0.0")
+            .text(
+                "language",
+                self.language.clone().unwrap_or_else(|| "auto".to_string()),
+            )
+            .text("prompt", self.whisper_prompt.clone())
+            .text(
+                "translate",
+                (task == TranscriptionTask::Translate).to_string(),
+            );
+
+        let response = self
+            .client
+            .post(format!("{}/inference", self.base_url))
+            .multipart(form)
+            .send()
+            .await
+            .context("Failed to send whisper-server request")?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .context("Failed to read whisper-server response")?;
+
+        let parsed = serde_json::from_str::<InferenceResponse>(&body).ok();
+        if let Some(error) = parsed.as_ref().and_then(|parsed| parsed.error.clone()) {
+            return Err(anyhow!("whisper-server error ({}): {}", status, error));
+        }
+        if !status.is_success() {
+            return Err(anyhow!(
+                "whisper-server request failed ({}): {}",
+                status,
+                body.trim()
+            ));
+        }
+
+        match parsed {
+            Some(InferenceResponse { transcript, .. }) if transcript.text.is_some() => {
+                Ok(transcript)
+            }
+            _ => Err(anyhow!(
+                "Unexpected whisper-server response: {}",
+                body.trim()
+            )),
+        }
+    }
+}
+
+/// `/inference` answers with a verbose_json transcript, or `{"error": ..}` (sometimes
+/// with a 200 status)
+#[derive(Debug, Deserialize)]
+struct InferenceResponse {
+    #[serde(flatten)]
+    transcript: VerboseTranscription,
+    error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn vad(enabled: bool) -> WhisperVadOptions {
+        WhisperVadOptions {
+            enabled,
+            model_path: Some(PathBuf::from("/models/ggml-silero.bin")),
+            ..WhisperVadOptions::disabled()
+        }
+    }
+
+    fn launch(gpu_layers: i32, vad: WhisperVadOptions) -> Vec<String> {
+        Launch::new(
+            PathBuf::from("whisper-server"),
+            Path::new("/models/ggml-base.en.bin"),
+            "127.0.0.1",
+            8178,
+            4,
+            gpu_layers,
+            &vad,
+            0.6,
+        )
+        .args
+        .into_iter()
+        .map(|arg| arg.into_string().unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn launch_args_mirror_whisper_cli_settings() {
+        let args = launch(999, vad(false)).join(" ");
+        assert!(args.contains("-m /models/ggml-base.en.bin"));
+        assert!(args.contains("--host 127.0.0.1 --port 8178"));
+        assert!(args.contains("--threads 4"));
+        assert!(!args.contains("--no-gpu"));
+        assert!(!args.contains("--vad"));
+        // The language goes with each request, so changing it keeps the server running
+        assert!(!args.contains("--language"));
+
+        let args = launch(0, vad(true)).join(" ");
+        assert!(args.contains("--no-gpu"));
+        assert!(args.contains("--vad --vad-model /models/ggml-silero.bin"));
+    }
+
+    /// Pids logged by a stand-in server binary, once `count` of them have started
+    async fn started_pids(log: &Path, count: usize) -> Vec<u32> {
+        for _ in 0..100 {
+            let pids: Vec<u32> = std::fs::read_to_string(log)
+                .unwrap_or_default()
+                .lines()
+                .map(|line| line.parse().unwrap())
+                .collect();
+            if pids.len() >= count {
+                return pids;
+            }
+            sleep(Duration::from_millis(50)).await;
+        }
+        panic!("stand-in whisper-server did not start {count} times");
+    }
+
+    #[tokio::test]
+    async fn rebuilt_backends_share_the_server_until_its_launch_settings_change() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir =
+            std::env::temp_dir().join(format!("hyprwhspr-server-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log = dir.join("pids");
+        let binary = dir.join("whisper-server");
+        std::fs::write(
+            &binary,
+            format!("#!/bin/sh\necho $$ >> {:?}\nexec sleep 30\n", log),
+        )
+        .unwrap();
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let launch = |threads| {
+            Launch::new(
+                binary.clone(),
+                Path::new("/models/ggml-base.en.bin"),
+                "127.0.0.1",
+                8178,
+                threads,
+                999,
+                &vad(false),
+                0.6,
+            )
+        };
+        let address = "rebuild-test:8178";
+
+        let first = ManagedServer::acquire(address, launch(4));
+        let same = ManagedServer::acquire(address, launch(4));
+        assert!(Arc::ptr_eq(&first, &same));
+        let first_pid = started_pids(&log, 1).await[0];
+
+        // The replacement only starts once the old child is gone, even while jobs hold it
+        let replaced = ManagedServer::acquire(address, launch(8));
+        assert!(!Arc::ptr_eq(&first, &replaced));
+        started_pids(&log, 2).await;
+        assert!(!Path::new(&format!("/proc/{first_pid}")).exists());
+
+        drop((first, same, replaced));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Answers `/health` with 503 until `loading_polls` requests have been seen, then 200.
+    /// Every `/inference` request gets `inference_body`.
+    async fn stub_server(loading_polls: usize, inference_body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            let mut health_polls = 0;
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut raw = Vec::new();
+                let mut buf = [0u8; 8192];
+                loop {
+                    let read = stream.read(&mut buf).await.unwrap();
+                    raw.extend_from_slice(&buf[..read]);
+                    let text = String::from_utf8_lossy(&raw);
+                    let complete = text.starts_with("GET ") && text.contains("\r\n\r\n")
+                        || text.trim_end().ends_with("--");
+                    if complete || read == 0 {
+                        break;
+                    }
+                }
+
+                let (status, body) = if raw.starts_with(b"GET /health") {
+                    health_polls += 1;
+                    if health_polls > loading_polls {
+                        (200, r#"{"status":"ok"}"#)
+                    } else {
+                        (503, r#"{"status":"loading model"}"#)
+                    }
+                } else {
+                    (200, inference_body)
+                };
+                let reply = format!(
+                    "HTTP/1.1 {status} Stub\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(reply.as_bytes()).await.unwrap();
+                stream.shutdown().await.unwrap();
+            }
+        });
+
+        url
+    }
+
+    fn attached(url: &str, language: Option<&str>) -> WhisperServer {
+        WhisperServer::new(
+            Some(url),
+            None,
+            "127.0.0.1",
+            0,
+            PathBuf::from("/models/ggml-base.en.bin"),
+            4,
+            "Hello.".to_string(),
+            language.map(str::to_string),
+            999,
+            WhisperVadOptions::disabled(),
+            0.6,
+            Duration::from_secs(5),
+            Duration::from_secs(5),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn waits_for_model_load_then_transcribes() {
+        let url = stub_server(2, r#"{"text":" hello world\n"}"#).await;
+        let server = attached(&url, Some("en"));
+
+        let result = server
+            .transcribe(vec![0.1; 1600], TranscriptionTask::Transcribe)
+            .await
+            .unwrap();
+        assert_eq!(result.text, "hello world");
+        assert_eq!(result.metrics.encoded_bytes, Some(44 + 3200));
+        assert_eq!(result.language, None);
+    }
+
+    #[tokio::test]
+    async fn reports_auto_detected_language_and_segments() {
+        let url = stub_server(
+            0,
+            r#"{"text":" hallo welt","language":"german","segments":[{"id":0,"text":" hallo welt","start":0.0,"end":0.1,"words":[{"word":" hallo","start":0.0,"end":0.05,"probability":0.9},{"word":" welt","start":0.05,"end":0.1,"probability":0.8}]}]}"#,
+        )
+        .await;
+        let server = attached(&url, None);
+
+        let result = server
+            .transcribe(vec![0.1; 1600], TranscriptionTask::Transcribe)
+            .await
+            .unwrap();
+        assert_eq!(result.text, "hallo welt");
+        assert_eq!(result.language.as_deref(), Some("german"));
+        assert_eq!(result.segments.len(), 1);
+        assert_eq!(result.segments[0].words[1].text, "welt");
+        assert_eq!(result.segments[0].words[1].probability, Some(0.8));
+    }
+
+    #[tokio::test]
+    async fn surfaces_server_errors() {
+        let url = stub_server(0, r#"{"error":"failed to read WAV file"}"#).await;
+        let server = attached(&url, Some("en"));
+
+        let err = server
+            .transcribe(vec![0.1; 1600], TranscriptionTask::Transcribe)
+            .await
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("failed to read WAV file"));
+    }
+}
@@ -26,3 +26,386 @@ fn default_config_round_trips() {
     let decoded: Config = serde_json::from_str(&json).expect("deserialize config");
     assert_eq!(decoded, config);
 }
+
+#[test]
+fn cancel_shortcut_is_optional_and_trimmed() {
+    let mut config: Config = serde_json::from_str("{}").expect("deserialize config");
+    config.normalize_shortcuts();
+    assert_eq!(config.cancel_shortcut(), None);
+
+    let json = r#"{"shortcuts":{"press":"SUPER+ALT+D","cancel":"  SUPER+ALT+ESC "}}"#;
+    let mut config: Config = serde_json::from_str(json).expect("deserialize config");
+    config.normalize_shortcuts();
+    assert_eq!(config.cancel_shortcut(), Some("SUPER+ALT+ESC"));
+}
+
+#[test]
+fn max_recording_defaults_and_can_be_disabled() {
+    let config: Config = serde_json::from_str("{}").expect("deserialize config");
+    assert_eq!(config.max_recording_secs, 300);
+    assert_eq!(config.max_recording_warning_secs, 10);
+
+    let json = r#"{"max_recording_secs":0,"max_recording_warning_secs":0}"#;
+    let config: Config = serde_json::from_str(json).expect("deserialize config");
+    assert_eq!(config.max_recording_secs, 0);
+    assert_eq!(config.max_recording_warning_secs, 0);
+}
+
+#[test]
+fn streaming_is_opt_in() {
+    let config: Config = serde_json::from_str("{}").expect("deserialize config");
+    assert!(!config.streaming.enabled);
+
+    let json = r#"{"streaming":{"enabled":true,"pause_ms":500}}"#;
+    let config: Config = serde_json::from_str(json).expect("deserialize config");
+    assert!(config.streaming.enabled);
+    assert_eq!(config.streaming.pause_ms, 500);
+    assert_eq!(config.streaming.min_chunk_ms, 2000);
+}
+
+#[test]
+fn openai_compatible_provider_deserializes() {
+    let json = r#"{"transcription":{"provider":"openai_compatible","openai_compatible":{"base_url":"http://gpu-box:8000/v1","response_format":"verbose_json","extra_fields":{"language":"de"}}}}"#;
+    let config: Config = serde_json::from_str(json).expect("deserialize config");
+    let openai = &config.transcription.openai_compatible;
+    assert_eq!(openai.base_url, "http://gpu-box:8000/v1");
+    assert_eq!(openai.response_format.as_str(), "verbose_json");
+    assert_eq!(
+        openai.extra_fields.get("language").map(String::as_str),
+        Some("de")
+    );
+    assert_eq!(openai.api_key_env, None);
+}
+
+#[test]
+fn provider_chain_lists_primary_then_unique_fallbacks() {
+    let json = r#"{"transcription":{"provider":"groq","fallback":["parakeet","groq","whisper_cpp","parakeet"]}}"#;
+    let config: Config = serde_json::from_str(json).expect("deserialize config");
+    let labels: Vec<_> = config
+        .transcription
+        .provider_chain()
+        .iter()
+        .map(|provider| provider.label())
+        .collect();
+    assert_eq!(labels, ["Groq", "Parakeet TDT", "Local"]);
+
+    let config: Config = serde_json::from_str("{}").expect("deserialize config");
+    assert_eq!(config.transcription.provider_chain().len(), 1);
+}
+
+#[test]
+fn language_defaults_to_english_and_auto_means_detect() {
+    let config: Config = serde_json::from_str("{}").expect("deserialize config");
+    assert_eq!(config.transcription.language_code().as_deref(), Some("en"));
+
+    let json = r#"{"transcription":{"language":" DE "}}"#;
+    let config: Config = serde_json::from_str(json).expect("deserialize config");
+    assert_eq!(config.transcription.language_code().as_deref(), Some("de"));
+
+    let json = r#"{"transcription":{"language":"auto"}}"#;
+    let config: Config = serde_json::from_str(json).expect("deserialize config");
+    assert_eq!(config.transcription.language_code(), None);
+}
+
+#[test]
+fn translate_can_be_global_or_bound_to_its_own_shortcut() {
+    let mut config: Config = serde_json::from_str("{}").expect("deserialize config");
+    config.normalize_shortcuts();
+    assert!(!config.transcription.translate);
+    assert_eq!(config.translate_shortcut(), None);
+
+    let json = r#"{"shortcuts":{"translate":" SUPER+ALT+T "},"transcription":{"translate":true}}"#;
+    let mut config: Config = serde_json::from_str(json).expect("deserialize config");
+    config.normalize_shortcuts();
+    assert!(config.transcription.translate);
+    assert_eq!(config.translate_shortcut(), Some("SUPER+ALT+T"));
+}
+
+#[test]
+fn hallucination_guard_is_on_by_default_and_configurable() {
+    let config: Config = serde_json::from_str("{}").expect("deserialize config");
+    let guard = &config.transcription.hallucination_guard;
+    assert!(guard.enabled);
+    assert!(guard
+        .phrases
+        .iter()
+        .any(|phrase| phrase == "Thanks for watching"));
+
+    let json = r#"{"transcription":{"hallucination_guard":{"phrases":["Ende"],"max_repeats":0}}}"#;
+    let config: Config = serde_json::from_str(json).expect("deserialize config");
+    let guard = &config.transcription.hallucination_guard;
+    assert!(guard.enabled);
+    assert_eq!(guard.phrases, ["Ende"]);
+    assert_eq!(guard.max_repeats, 0);
+    assert_eq!(guard.max_chars_per_second, 40.0);
+}
+
+#[test]
+fn llm_postprocess_uses_the_selected_profile_prompt() {
+    let config: Config = serde_json::from_str("{}").expect("deserialize config");
+    let llm = &config.llm_postprocess;
+    assert!(!llm.enabled);
+    assert_eq!(llm.system_prompt().unwrap(), llm.prompt);
+
+    let json = r#"{"llm_postprocess":{"enabled":true,"profiles":{"commit":"Write a commit message"},"profile":"commit"}}"#;
+    let mut config: Config = serde_json::from_str(json).expect("deserialize config");
+    assert_eq!(
+        config.llm_postprocess.system_prompt().unwrap(),
+        "Write a commit message"
+    );
+
+    config.llm_postprocess.profile = Some("email".to_string());
+    assert!(config.llm_postprocess.system_prompt().is_err());
+}
+
+#[test]
+fn voice_commands_default_to_the_built_in_vocabulary() {
+    let config: Config = serde_json::from_str("{}").expect("deserialize config");
+    assert!(!config.voice_commands.enabled);
+    assert!(!config.voice_commands.case_formatting);
+    assert_eq!(
+        config
+            .voice_commands
+            .commands
+            .get("scratch that")
+            .map(String::as_str),
+        Some("delete_last")
+    );
+
+    let json = r#"{"voice_commands":{"commands":{"send it":"CTRL+Return"}}}"#;
+    let config: Config = serde_json::from_str(json).expect("deserialize config");
+    assert_eq!(config.voice_commands.commands.len(), 1);
+    assert_eq!(config.voice_commands.commands["send it"], "CTRL+Return");
+}
+
+#[test]
+fn speech_replacements_follow_the_language_unless_packs_are_set() {
+    let config: Config = serde_json::from_str("{}").expect("deserialize config");
+    assert!(config.speech_replacements.packs.is_none());
+    assert!(config.speech_replacements.entries.is_empty());
+
+    let json = r#"{"speech_replacements":{"packs":["de"],"entries":[{"phrase":"arrow","replacement":"->"}]}}"#;
+    let config: Config = serde_json::from_str(json).expect("deserialize config");
+    assert_eq!(
+        config.speech_replacements.packs,
+        Some(vec!["de".to_string()])
+    );
+    let entry = &config.speech_replacements.entries[0];
+    assert_eq!(
+        (entry.phrase.as_str(), entry.replacement.as_str()),
+        ("arrow", "->")
+    );
+    assert!(!entry.adjust_preceding_punct);
+
+    let serialized = serde_json::to_value(&config).expect("serialize config");
+    assert_eq!(serialized["speech_replacements"]["packs"][0], "de");
+}
+
+#[test]
+fn word_override_rules_keep_their_order_and_options() {
+    let json = r#"{"word_override_rules":[
+        {"pattern":"(\\d+) percent","replacement":"$1%","regex":true},
+        {"pattern":"lgtm","replacement":"looks good to me","window_classes":["Slack"]}
+    ]}"#;
+    let config: Config = serde_json::from_str(json).expect("deserialize config");
+    let rules = &config.word_override_rules;
+    assert_eq!(rules.len(), 2);
+    assert!(rules[0].regex && !rules[0].preserve_case);
+    assert!(rules[0].window_classes.is_empty());
+    assert_eq!(rules[1].pattern, "lgtm");
+    assert_eq!(rules[1].window_classes, ["Slack"]);
+
+    let defaults = serde_json::to_value(Config::default()).expect("serialize config");
+    assert!(defaults.get("word_override_rules").is_none());
+}
+
+#[test]
+fn window_profiles_override_the_base_config() {
+    let json = r#"{
+        "trailing_whitespace": " ",
+        "word_overrides": {"hyperland": "hyprland"},
+        "llm_postprocess": {"profiles": {"commit": "Rewrite as a commit message"}},
+        "profiles": [
+            {
+                "name": "terminal",
+                "window": {"class": ["kitty", "foot"]},
+                "provider": "groq",
+                "prompt": "Shell commands.",
+                "word_overrides": {"pipe": "|"},
+                "llm_profile": "commit",
+                "trailing_whitespace": "",
+                "paste_method": "ctrl_shift_v"
+            },
+            {"name": "slack", "window": {"class": ["Slack"], "title": "^#?general"}, "language": "de"},
+            {"name": "manual"}
+        ]
+    }"#;
+    let config: Config = serde_json::from_str(json).expect("deserialize config");
+
+    let matched = |class: &str, title: &str| {
+        config
+            .window_profile(Some(class), Some(title))
+            .map(|profile| profile.name.as_str())
+    };
+    assert_eq!(matched("KITTY", "~"), Some("terminal"));
+    assert_eq!(matched("Slack", "general | Slack"), Some("slack"));
+    assert_eq!(matched("Slack", "random | Slack"), None);
+    assert_eq!(matched("firefox", "general"), None);
+
+    let terminal = config.with_profile("terminal").expect("apply profile");
+    assert_eq!(
+        terminal.transcription.provider,
+        hyprwhspr_rs::config::TranscriptionProvider::Groq
+    );
+    assert_eq!(terminal.transcription.groq.prompt, "Shell commands.");
+    assert_eq!(terminal.transcription.whisper_cpp.prompt, "Shell commands.");
+    assert_eq!(terminal.word_overrides.len(), 2);
+    assert!(terminal.llm_postprocess.enabled);
+    assert_eq!(
+        terminal.llm_postprocess.system_prompt().unwrap(),
+        "Rewrite as a commit message"
+    );
+    assert_eq!(terminal.trailing_whitespace, "");
+    assert_eq!(
+        terminal.paste_method,
+        hyprwhspr_rs::config::PasteMethod::CtrlShiftV
+    );
+
+    let slack = config.with_profile("slack").expect("apply profile");
+    assert_eq!(slack.transcription.language, "de");
+    assert_eq!(slack.trailing_whitespace, " ");
+    assert!(config.with_profile("missing").is_err());
+}
+
+#[test]
+fn invalid_window_title_patterns_are_reported_at_load_and_match_nothing() {
+    let json = r#"{
+        "profiles": [
+            {"name": "broken", "window": {"title": "(unclosed"}},
+            {"name": "editor", "window": {"title": "\\.rs$"}}
+        ]
+    }"#;
+    let config: Config = serde_json::from_str(json).expect("deserialize config");
+
+    let title = |index: usize| {
+        config.profiles[index]
+            .window
+            .as_ref()
+            .and_then(|window| window.title.as_ref())
+            .expect("title pattern")
+    };
+    assert!(title(0).error().is_some());
+    assert!(title(1).error().is_none());
+    assert_eq!(
+        config
+            .window_profile(None, Some("(unclosed"))
+            .map(|profile| profile.name.as_str()),
+        None
+    );
+    assert_eq!(
+        config
+            .window_profile(None, Some("main.rs"))
+            .map(|profile| profile.name.as_str()),
+        Some("editor")
+    );
+
+    let value = serde_json::to_value(&config).expect("serialize config");
+    assert_eq!(value["profiles"][0]["window"]["title"], "(unclosed");
+}
+
+#[test]
+fn active_profile_cycles_through_profiles_and_back_to_none() {
+    let config: Config = serde_json::from_str("{}").expect("deserialize config");
+    assert!(config.active_profile.is_none());
+    assert!(config.cycle_profile(None, true).is_none());
+
+    let json = r#"{
+        "profiles": [{ "name": "english" }, { "name": "german", "language": "de" }],
+        "active_profile": "german"
+    }"#;
+    let config: Config = serde_json::from_str(json).expect("deserialize config");
+    assert_eq!(config.active_profile.as_deref(), Some("german"));
+
+    let name = |profile: Option<&hyprwhspr_rs::config::ProfileConfig>| {
+        profile.map(|profile| profile.name.clone())
+    };
+    assert_eq!(
+        name(config.cycle_profile(None, true)).as_deref(),
+        Some("english")
+    );
+    assert_eq!(
+        name(config.cycle_profile(Some("english"), true)).as_deref(),
+        Some("german")
+    );
+    assert_eq!(name(config.cycle_profile(Some("german"), true)), None);
+    assert_eq!(
+        name(config.cycle_profile(None, false)).as_deref(),
+        Some("german")
+    );
+    assert_eq!(name(config.cycle_profile(Some("english"), false)), None);
+    assert_eq!(
+        name(config.cycle_profile(Some("removed"), true)).as_deref(),
+        Some("english")
+    );
+}
+
+#[test]
+fn shortcut_bindings_follow_the_fixed_slots() {
+    use hyprwhspr_rs::config::ShortcutAction;
+
+    let json = r#"{
+        "shortcuts": {
+            "cancel": "SUPER+ALT+ESC",
+            "bindings": [
+                { "keys": " SUPER+ALT+G ", "profile": "Gemini Pro" },
+                { "keys": "SUPER+ALT+C", "action": "command", "hold": true },
+                { "keys": "SUPER+ALT+V", "action": "reinject" },
+                { "keys": "  " }
+            ]
+        }
+    }"#;
+    let mut config: Config = serde_json::from_str(json).expect("deserialize config");
+    config.normalize_shortcuts();
+
+    let bindings = config.shortcuts.all_bindings();
+    let summary: Vec<_> = bindings
+        .iter()
+        .map(|binding| (binding.keys.as_str(), binding.action, binding.hold))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("SUPER+ALT+R", ShortcutAction::Dictate, false),
+            ("SUPER+ALT+ESC", ShortcutAction::Cancel, false),
+            ("SUPER+ALT+G", ShortcutAction::Dictate, false),
+            ("SUPER+ALT+C", ShortcutAction::Command, true),
+            ("SUPER+ALT+V", ShortcutAction::Reinject, false),
+        ]
+    );
+    assert_eq!(bindings[2].profile.as_deref(), Some("Gemini Pro"));
+    assert!(bindings[3].action.records());
+    assert!(!bindings[4].action.records());
+}
+
+#[test]
+fn typing_can_be_selected_globally_or_per_window_class() {
+    use hyprwhspr_rs::config::PasteMethod;
+
+    let config: Config = serde_json::from_str("{}").expect("deserialize config");
+    assert_eq!(config.paste_method, PasteMethod::Auto);
+    assert_eq!(config.type_delay_ms, 0);
+    assert!(config.paste_hints.type_text.is_empty());
+
+    let json = r#"{
+        "paste_method": "type",
+        "type_delay_ms": 8,
+        "paste_hints": { "type": ["org.keepassxc.KeePassXC"] }
+    }"#;
+    let config: Config = serde_json::from_str(json).expect("deserialize config");
+    assert_eq!(config.paste_method, PasteMethod::Type);
+    assert_eq!(config.type_delay_ms, 8);
+    assert_eq!(config.paste_hints.type_text, ["org.keepassxc.KeePassXC"]);
+
+    let json = serde_json::to_string(&config).expect("serialize config");
+    assert!(json.contains(r#""type":["org.keepassxc.KeePassXC"]"#));
+}
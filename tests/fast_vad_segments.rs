@@ -1,4 +1,4 @@
-use hyprwhspr_rs::audio::FastVad;
+use hyprwhspr_rs::audio::{EndOfSpeechDetector, FastVad};
 use hyprwhspr_rs::config::FastVadConfig;
 use std::f32::consts::PI;
 
@@ -62,3 +62,65 @@ fn silence_short_circuits_transmission() {
     assert_eq!(outcome.segments, 0);
     assert_eq!(outcome.dropped_samples, audio.len());
 }
+
+fn feed_in_chunks(detector: &mut EndOfSpeechDetector, audio: &[f32]) -> Option<usize> {
+    // 100 ms chunks, the same cadence the daemon polls the capture buffer at
+    let chunk = (TEST_SAMPLE_RATE_HZ / 10) as usize;
+    for (index, samples) in audio.chunks(chunk).enumerate() {
+        if detector.push(samples).expect("live VAD should process audio") {
+            return Some(index);
+        }
+    }
+    None
+}
+
+#[test]
+fn end_of_speech_fires_after_trailing_silence() {
+    let config = FastVadConfig {
+        auto_stop: true,
+        auto_stop_silence_ms: Some(600),
+        ..Default::default()
+    };
+    let mut detector = EndOfSpeechDetector::new(&config, TEST_SAMPLE_RATE_HZ)
+        .expect("live VAD initialization should succeed");
+
+    let mut audio = Vec::new();
+    audio.extend(silence_ms(300));
+    audio.extend(tone_ms(800));
+    audio.extend(silence_ms(2000));
+
+    let stopped_at = feed_in_chunks(&mut detector, &audio).expect("silence should end speech");
+    assert!(detector.heard_speech());
+    // 300 ms lead-in + 800 ms speech + 600 ms timeout, give or take a chunk
+    assert!((16..=19).contains(&stopped_at), "stopped at chunk {stopped_at}");
+}
+
+#[test]
+fn end_of_speech_waits_for_speech_first() {
+    let config = FastVadConfig {
+        auto_stop: true,
+        ..Default::default()
+    };
+    let mut detector = EndOfSpeechDetector::new(&config, TEST_SAMPLE_RATE_HZ)
+        .expect("live VAD initialization should succeed");
+
+    assert_eq!(feed_in_chunks(&mut detector, &silence_ms(3000)), None);
+    assert!(!detector.heard_speech());
+}
+
+#[test]
+fn pause_detector_rearms_after_reset() {
+    let config = FastVadConfig::default();
+    let mut detector = EndOfSpeechDetector::with_silence_ms(&config, TEST_SAMPLE_RATE_HZ, 400)
+        .expect("live VAD initialization should succeed");
+
+    let mut utterance = tone_ms(800);
+    utterance.extend(silence_ms(1000));
+
+    assert!(feed_in_chunks(&mut detector, &utterance).is_some());
+    detector.reset();
+    assert!(!detector.heard_speech());
+
+    // A second phrase after the cut ends at its own pause
+    assert!(feed_in_chunks(&mut detector, &utterance).is_some());
+}